@@ -1,5 +1,5 @@
 /**
- * Hook to fetch claims issued by an account using get_issuer_claims
+ * Hook to fetch claims issued by an account using get_issuer_claims_page
  */
 
 import { useState, useEffect, useCallback } from 'react';
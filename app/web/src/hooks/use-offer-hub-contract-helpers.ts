@@ -26,6 +26,12 @@ export function parseProfile(val: xdr.ScVal | undefined): Profile {
   };
 }
 
+// Unit enum variants come back from scValToNative as `[name]`
+export function parseClaimStatus(status: unknown): ClaimStatus {
+  const name = Array.isArray(status) ? status[0] : status;
+  return (name?.toString() || 'Pending') as ClaimStatus;
+}
+
 export function parseClaim(val: xdr.ScVal | undefined): Claim {
   if (!val) return {} as Claim;
   
@@ -37,8 +43,7 @@ export function parseClaim(val: xdr.ScVal | undefined): Claim {
     ? native.proof_hash 
     : new Uint8Array(32);
 
-  // Claims are created directly as Approved
-  const status: ClaimStatus = 'Approved';
+  const status = parseClaimStatus(native.status);
   
   return {
     id: Number(native.id),
@@ -50,6 +55,27 @@ export function parseClaim(val: xdr.ScVal | undefined): Claim {
   };
 }
 
+// Pages come back as `{ claims, next_cursor }`; `next_cursor` is null on the last page
+export function parseClaimPage(val: xdr.ScVal | undefined): { claims: Claim[]; nextCursor: number | null } {
+  if (!val) return { claims: [], nextCursor: null };
+
+  const native = scValToNative(val);
+  if (!native) return { claims: [], nextCursor: null };
+
+  const claims = Array.isArray(native.claims) ? native.claims : [];
+  return {
+    claims: claims.map((n: any) => ({
+      id: Number(n.id),
+      issuer: n.issuer?.toString() || '',
+      receiver: n.receiver?.toString() || '',
+      claim_type: n.claim_type?.toString() || '',
+      proof_hash: n.proof_hash instanceof Uint8Array ? n.proof_hash : new Uint8Array(32),
+      status: parseClaimStatus(n.status),
+    })),
+    nextCursor: native.next_cursor == null ? null : Number(native.next_cursor),
+  };
+}
+
 export function parseClaimArray(val: xdr.ScVal | undefined): Claim[] {
   if (!val) return [];
   try {
@@ -68,7 +94,7 @@ export function parseClaimArray(val: xdr.ScVal | undefined): Claim[] {
         receiver: n.receiver?.toString() || '',
         claim_type: n.claim_type?.toString() || '',
         proof_hash: n.proof_hash instanceof Uint8Array ? n.proof_hash : new Uint8Array(32),
-        status: 'Approved' as ClaimStatus,
+        status: parseClaimStatus(n.status),
       }));
     }
     return [];
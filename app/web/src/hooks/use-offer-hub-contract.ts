@@ -5,6 +5,8 @@ import { CONTRACT_CONFIG } from '../config/contract';
 import type {
   Profile,
   Claim,
+  ClaimEvidence,
+  LinkedAccount,
   RegisterProfileParams,
   UpdateProfileParams,
   AddClaimParams,
@@ -12,9 +14,12 @@ import type {
 import { 
   parseProfile, 
   parseClaim, 
-  parseClaimArray 
+  parseClaimPage 
 } from './use-offer-hub-contract-helpers';
 
+// Largest page the contract returns (`MAX_PAGE_SIZE`)
+const CLAIM_PAGE_SIZE = 50;
+
 interface UseOfferHubContractReturn {
   isReady: boolean;
   error: string | null;
@@ -232,26 +237,13 @@ export function useOfferHubContract(): UseOfferHubContractReturn {
   // Write functions
   const registerProfile = useCallback(async (params: Omit<RegisterProfileParams, 'owner'>) => {
     try {
-      // Build linked accounts as ScVal maps (structs in Soroban are represented as maps)
-      // CRITICAL: Keys in ScMap MUST be sorted! ('handle' comes before 'platform')
-      const linkedAccountsScVal = params.linked_accounts.map(acc => {
-        const mapEntries = [
-          new xdr.ScMapEntry({
-            key: xdr.ScVal.scvSymbol('handle'),
-            val: xdr.ScVal.scvString(acc.handle)
-          }),
-          new xdr.ScMapEntry({
-            key: xdr.ScVal.scvSymbol('platform'),
-            val: xdr.ScVal.scvSymbol(acc.platform)
-          })
-        ];
-        return xdr.ScVal.scvMap(mapEntries);
-      });
+      const linkedAccountsScVal = params.linked_accounts.map(linkedAccountToScVal);
 
       // Build arguments array
       const args: xdr.ScVal[] = [
         xdr.ScVal.scvAddress(StellarAddress.fromString(issuerAddress).toScAddress()),
         xdr.ScVal.scvString(params.metadata_uri),
+        params.metadata_hash ? xdr.ScVal.scvBytes(Buffer.from(params.metadata_hash)) : xdr.ScVal.scvVoid(),
         xdr.ScVal.scvString(params.display_name),
         params.country_code ? xdr.ScVal.scvSymbol(params.country_code) : xdr.ScVal.scvVoid(),
         params.email_hash ? xdr.ScVal.scvBytes(Buffer.from(params.email_hash)) : xdr.ScVal.scvVoid(),
@@ -266,23 +258,13 @@ export function useOfferHubContract(): UseOfferHubContractReturn {
   }, [issuerAddress, invokeContract]);
 
   const updateProfileData = useCallback(async (params: Omit<UpdateProfileParams, 'owner'>) => {
-    const linkedAccountsScVal = params.linked_accounts.map(acc => 
-      xdr.ScVal.scvMap([
-        new xdr.ScMapEntry({
-          key: xdr.ScVal.scvSymbol('handle'),
-          val: xdr.ScVal.scvString(acc.handle)
-        }),
-        new xdr.ScMapEntry({
-          key: xdr.ScVal.scvSymbol('platform'),
-          val: xdr.ScVal.scvSymbol(acc.platform)
-        })
-      ])
-    );
+    const linkedAccountsScVal = params.linked_accounts.map(linkedAccountToScVal);
     
     const args = [
       xdr.ScVal.scvAddress(StellarAddress.fromString(issuerAddress).toScAddress()),
       xdr.ScVal.scvString(params.display_name),
       xdr.ScVal.scvString(params.metadata_uri),
+      params.metadata_hash ? xdr.ScVal.scvBytes(Buffer.from(params.metadata_hash)) : xdr.ScVal.scvVoid(),
       params.country_code ? xdr.ScVal.scvSymbol(params.country_code) : xdr.ScVal.scvVoid(),
       params.email_hash ? xdr.ScVal.scvBytes(Buffer.from(params.email_hash)) : xdr.ScVal.scvVoid(),
       xdr.ScVal.scvVec(linkedAccountsScVal)
@@ -300,6 +282,11 @@ export function useOfferHubContract(): UseOfferHubContractReturn {
       xdr.ScVal.scvAddress(StellarAddress.fromString(params.receiver).toScAddress()),
       xdr.ScVal.scvString(params.claim_type),
       xdr.ScVal.scvBytes(proofHashBuffer),
+      params.metadata_uri ? xdr.ScVal.scvString(params.metadata_uri) : xdr.ScVal.scvVoid(),
+      xdr.ScVal.scvVec((params.evidence ?? []).map(claimEvidenceToScVal)),
+      params.valid_from !== undefined || params.valid_until !== undefined
+        ? validityWindowToScVal(params.valid_from, params.valid_until)
+        : xdr.ScVal.scvVoid(),
     ];
     
     const result = await invokeContract('add_claim', args);
@@ -339,30 +326,36 @@ export function useOfferHubContract(): UseOfferHubContractReturn {
     return null;
   }, [invokeContract]);
 
-  const getUserClaims = useCallback(async (account: string): Promise<Claim[]> => {
-    const args = [
-      xdr.ScVal.scvAddress(StellarAddress.fromString(account).toScAddress()),
-    ];
-    
-    const result = await invokeContract('get_user_claims', args, false);
-    if ('result' in result && result.result?.retval) {
-      return parseClaimArray(result.result.retval);
-    }
-    return [];
-  }, [invokeContract]);
+  // The unpaged claim getters stop at one page, so read every page instead
+  const getAllClaimPages = useCallback(async (method: string, account: string): Promise<Claim[]> => {
+    const claims: Claim[] = [];
+    let cursor: number | null = 0;
+    while (cursor !== null) {
+      const args = [
+        xdr.ScVal.scvAddress(StellarAddress.fromString(account).toScAddress()),
+        xdr.ScVal.scvU32(cursor),
+        xdr.ScVal.scvU32(CLAIM_PAGE_SIZE),
+      ];
 
-  const getIssuerClaims = useCallback(async (account: string): Promise<Claim[]> => {
-    const args = [
-      xdr.ScVal.scvAddress(StellarAddress.fromString(account).toScAddress()),
-    ];
-    
-    const result = await invokeContract('get_issuer_claims', args, false);
-    if ('result' in result && result.result?.retval) {
-      return parseClaimArray(result.result.retval);
+      const result = await invokeContract(method, args, false);
+      if (!('result' in result && result.result?.retval)) break;
+      const page = parseClaimPage(result.result.retval);
+      claims.push(...page.claims);
+      cursor = page.nextCursor;
     }
-    return [];
+    return claims;
   }, [invokeContract]);
 
+  const getUserClaims = useCallback(
+    (account: string): Promise<Claim[]> => getAllClaimPages('get_user_claims_page', account),
+    [getAllClaimPages]
+  );
+
+  const getIssuerClaims = useCallback(
+    (account: string): Promise<Claim[]> => getAllClaimPages('get_issuer_claims_page', account),
+    [getAllClaimPages]
+  );
+
   const getTotalClaims = useCallback(async (): Promise<number> => {
     const result = await invokeContract('get_total_claims', [], false);
     if ('result' in result && result.result?.retval) {
@@ -427,3 +420,64 @@ function scValToString(val: xdr.ScVal): string | null {
   }
   return null;
 }
+
+// Structs are passed as ScVal maps whose keys MUST be sorted. The verification
+// fields are only set by verifiers, so the owner always sends them empty.
+function linkedAccountToScVal(acc: LinkedAccount): xdr.ScVal {
+  return xdr.ScVal.scvMap([
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('handle'),
+      val: xdr.ScVal.scvString(acc.handle)
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('platform'),
+      val: xdr.ScVal.scvSymbol(acc.platform)
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('proof_hash'),
+      val: xdr.ScVal.scvVoid()
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('verified_at'),
+      val: xdr.ScVal.scvVoid()
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('verified_by'),
+      val: xdr.ScVal.scvVoid()
+    })
+  ]);
+}
+
+function claimEvidenceToScVal(evidence: ClaimEvidence): xdr.ScVal {
+  return xdr.ScVal.scvMap([
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('hash'),
+      val: xdr.ScVal.scvBytes(Buffer.from(evidence.hash))
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('kind'),
+      val: xdr.ScVal.scvSymbol(evidence.kind)
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('uri'),
+      val: xdr.ScVal.scvString(evidence.uri)
+    })
+  ]);
+}
+
+function optionalU64ToScVal(value?: number): xdr.ScVal {
+  return value !== undefined ? xdr.ScVal.scvU64(xdr.Uint64.fromString(value.toString())) : xdr.ScVal.scvVoid();
+}
+
+function validityWindowToScVal(validFrom?: number, validUntil?: number): xdr.ScVal {
+  return xdr.ScVal.scvMap([
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('valid_from'),
+      val: optionalU64ToScVal(validFrom)
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('valid_until'),
+      val: optionalU64ToScVal(validUntil)
+    })
+  ]);
+}
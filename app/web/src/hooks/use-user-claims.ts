@@ -1,5 +1,5 @@
 /**
- * Hook to fetch claims received by an account using get_user_claims
+ * Hook to fetch claims received by an account using get_user_claims_page
  */
 
 import { useState, useEffect, useCallback } from 'react';
@@ -9,8 +9,8 @@ export type Address = string;
 // BytesN<32> - Hash de 32 bytes
 export type ProofHash = Uint8Array | Buffer;
 
-// Status de un claim (claims are created directly as Approved)
-export type ClaimStatus = 'Approved';
+// Status de un claim. `Expired` lo calculan los getters, nunca se guarda
+export type ClaimStatus = 'Pending' | 'Approved' | 'Rejected' | 'Revoked' | 'Expired';
 
 // ============================================================================
 // TIPOS DE DATOS DEL CONTRATO
@@ -22,6 +22,9 @@ export type ClaimStatus = 'Approved';
 export interface LinkedAccount {
   platform: string;
   handle: string;
+  verified_by?: Address | null; // Solo lo asigna un verificador aprobado
+  verified_at?: number | null;
+  proof_hash?: ProofHash | null;
 }
 
 /**
@@ -37,6 +40,15 @@ export interface Profile {
   joined_at: number; // u64 timestamp
 }
 
+/**
+ * Documento adjunto a un claim (entregable, contrato, reseña...)
+ */
+export interface ClaimEvidence {
+  kind: string; // Symbol
+  uri: string;
+  hash: ProofHash; // SHA-256 del documento
+}
+
 /**
  * Claim (afirmación/credencial)
  */
@@ -47,6 +59,9 @@ export interface Claim {
   claim_type: string;
   proof_hash: ProofHash;
   status: ClaimStatus;
+  metadata_uri?: string | null;
+  evidence?: ClaimEvidence[];
+  issued_at?: number | null; // u64 timestamp
 }
 
 // ============================================================================
@@ -58,7 +73,8 @@ export interface Claim {
  */
 export interface RegisterProfileParams {
   owner: Address;
-  metadata_uri: string; // Máximo 256 caracteres, no vacío
+  metadata_uri: string; // ipfs://, ar:// o https://, máximo 256 caracteres
+  metadata_hash?: ProofHash; // SHA-256 del documento de metadata
   display_name: string;
   country_code?: string; // Optional 2 letter code
   email_hash?: ProofHash;
@@ -72,6 +88,7 @@ export interface UpdateProfileParams {
   owner: Address;
   display_name: string;
   metadata_uri: string;
+  metadata_hash?: ProofHash;
   country_code?: string;
   email_hash?: ProofHash;
   linked_accounts: LinkedAccount[];
@@ -85,6 +102,10 @@ export interface AddClaimParams {
   receiver: Address;
   claim_type: string;
   proof_hash: ProofHash; // Exactamente 32 bytes
+  metadata_uri?: string;
+  evidence?: ClaimEvidence[];
+  valid_from?: number; // u64 timestamp
+  valid_until?: number; // u64 timestamp
 }
 
 /**
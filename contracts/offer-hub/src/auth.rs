@@ -1,12 +1,410 @@
-use soroban_sdk::String;
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, String, Symbol, Vec};
 use crate::errors::Error;
+use crate::migration::CURRENT_SCHEMA_VERSION;
+use crate::storage::{
+    get_admin, get_did_methods, get_platforms, get_profile, get_schema_version, get_uri_schemes, is_paused,
+};
+use crate::types::{ClaimEvidence, LinkedAccount, PauseScope, Profile, ProfileStatus, ValidityWindow};
 
-/// Validates metadata URI format
-/// Basic check for non-empty and reasonable length
-pub fn validate_metadata_uri(uri: &String) -> Result<(), Error> {
-    if uri.len() == 0 || uri.len() > 256 {
+/// Requires authorization from the contract admin and returns its address
+pub fn require_admin(e: &Env) -> Address {
+    let admin = get_admin(e);
+    admin.require_auth();
+    admin
+}
+
+/// Fails with `ContractPaused` if the contract or the given scope is paused.
+/// Claims also fail with `MigrationPending` between an upgrade and the end of
+/// `migrate`, while their indexes are only partly built.
+pub fn require_not_paused(e: &Env, scope: PauseScope) -> Result<(), Error> {
+    if is_paused(e, &PauseScope::All) || is_paused(e, &scope) {
+        return Err(Error::ContractPaused);
+    }
+    if scope == PauseScope::Claims && get_schema_version(e) < CURRENT_SCHEMA_VERSION {
+        return Err(Error::MigrationPending);
+    }
+    Ok(())
+}
+
+/// Get a profile that can be changed, failing if it doesn't exist or is
+/// deactivated or deleted
+pub fn require_active_profile(e: &Env, owner: &Address) -> Result<Profile, Error> {
+    let profile = get_profile(e, owner).ok_or(Error::ProfileNotFound)?;
+    match profile.status {
+        ProfileStatus::Active => Ok(profile),
+        ProfileStatus::Deactivated => Err(Error::ProfileDeactivated),
+        ProfileStatus::Deleted => Err(Error::ProfileDeleted),
+    }
+}
+
+/// Whether an account has a profile that is neither deactivated nor deleted
+pub fn is_active_profile(e: &Env, owner: &Address) -> bool {
+    get_profile(e, owner).is_some_and(|profile| profile.status == ProfileStatus::Active)
+}
+
+/// Maximum length of a metadata URI
+pub const MAX_METADATA_URI_LENGTH: u32 = 256;
+
+/// Maximum number of evidence entries on a claim
+pub const MAX_CLAIM_EVIDENCE: u32 = 10;
+
+/// URI schemes accepted until the admin sets an allowlist
+pub fn default_uri_schemes(e: &Env) -> Vec<String> {
+    let mut schemes = Vec::new(e);
+    schemes.push_back(String::from_str(e, "ipfs"));
+    schemes.push_back(String::from_str(e, "ar"));
+    schemes.push_back(String::from_str(e, "https"));
+    schemes
+}
+
+/// Get the metadata URI scheme allowlist currently in effect
+pub fn active_uri_schemes(e: &Env) -> Vec<String> {
+    get_uri_schemes(e).unwrap_or_else(|| default_uri_schemes(e))
+}
+
+/// A URI scheme is a lowercase letter followed by lowercase letters, digits, `+`, `-` or `.`
+fn is_valid_uri_scheme(scheme: &[u8]) -> bool {
+    !scheme.is_empty()
+        && scheme[0].is_ascii_lowercase()
+        && scheme
+            .iter()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.'))
+}
+
+/// Validates a URI scheme name for the scheme allowlist
+pub fn validate_uri_scheme(scheme: &String) -> Result<(), Error> {
+    let len = scheme.len() as usize;
+    if len == 0 || len > MAX_METADATA_URI_LENGTH as usize {
+        return Err(Error::InvalidMetadataUri);
+    }
+    let mut buf = [0u8; MAX_METADATA_URI_LENGTH as usize];
+    scheme.copy_into_slice(&mut buf[..len]);
+    if !is_valid_uri_scheme(&buf[..len]) {
         return Err(Error::InvalidMetadataUri);
     }
     Ok(())
 }
 
+/// Basic IPFS CID syntax: a CIDv0 (`Qm` followed by 44 base58btc characters)
+/// or a base32 CIDv1 (`b` followed by lowercase base32 characters)
+fn is_valid_cid(cid: &[u8]) -> bool {
+    const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    if cid.len() == 46 && cid.starts_with(b"Qm") {
+        return cid.iter().all(|c| BASE58.contains(c));
+    }
+    cid.len() >= 59
+        && cid[0] == b'b'
+        && cid[1..].iter().all(|c| c.is_ascii_lowercase() || (b'2'..=b'7').contains(c))
+}
+
+/// Validates a metadata URI: `<scheme>://<location>` of at most 256 bytes,
+/// with an allowed scheme. `ipfs://` URIs must start with a valid CID.
+pub fn validate_metadata_uri(e: &Env, uri: &String) -> Result<(), Error> {
+    let len = uri.len() as usize;
+    if len == 0 || len > MAX_METADATA_URI_LENGTH as usize {
+        return Err(Error::InvalidMetadataUri);
+    }
+    let mut buf = [0u8; MAX_METADATA_URI_LENGTH as usize];
+    uri.copy_into_slice(&mut buf[..len]);
+    let uri = &buf[..len];
+
+    let sep = uri.windows(3).position(|w| w == b"://").ok_or(Error::InvalidMetadataUri)?;
+    let (scheme, location) = (&uri[..sep], &uri[sep + 3..]);
+    if !is_valid_uri_scheme(scheme) || location.is_empty() {
+        return Err(Error::InvalidMetadataUri);
+    }
+
+    if !active_uri_schemes(e).contains(String::from_bytes(e, scheme)) {
+        return Err(Error::UriSchemeNotAllowed);
+    }
+
+    // The CID is followed by an optional path
+    if scheme == b"ipfs" {
+        let cid_end = location.iter().position(|c| *c == b'/').unwrap_or(location.len());
+        if !is_valid_cid(&location[..cid_end]) {
+            return Err(Error::InvalidIpfsCid);
+        }
+    }
+    Ok(())
+}
+
+/// Maximum length of a display name
+pub const MAX_DISPLAY_NAME_LENGTH: u32 = 64;
+
+/// Maximum number of linked accounts per profile
+pub const MAX_LINKED_ACCOUNTS: u32 = 10;
+
+/// Maximum length of a linked account handle
+pub const MAX_LINKED_HANDLE_LENGTH: u32 = 64;
+
+/// ISO 3166-1 alpha-2 country codes
+const COUNTRY_CODES: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Validates a display name: non-empty and at most 64 bytes
+pub fn validate_display_name(display_name: &String) -> Result<(), Error> {
+    if display_name.is_empty() || display_name.len() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(Error::InvalidDisplayName);
+    }
+    Ok(())
+}
+
+/// Validates an ISO 3166-1 alpha-2 country code (e.g. `AR`)
+pub fn validate_country_code(e: &Env, country_code: &Symbol) -> Result<(), Error> {
+    if !COUNTRY_CODES.iter().any(|code| Symbol::new(e, code) == *country_code) {
+        return Err(Error::InvalidCountryCode);
+    }
+    Ok(())
+}
+
+/// Platforms accepted until the admin sets an allowlist
+pub fn default_platforms(e: &Env) -> Vec<Symbol> {
+    let mut platforms = Vec::new(e);
+    for platform in ["github", "gitlab", "linkedin", "x", "behance", "dribbble"] {
+        platforms.push_back(Symbol::new(e, platform));
+    }
+    platforms
+}
+
+/// Get the linked account platform allowlist currently in effect
+pub fn active_platforms(e: &Env) -> Vec<Symbol> {
+    get_platforms(e).unwrap_or_else(|| default_platforms(e))
+}
+
+/// Validates the linked accounts of a profile: at most 10, each on an
+/// allowed platform and no platform twice
+pub fn validate_linked_accounts(e: &Env, linked_accounts: &Vec<LinkedAccount>) -> Result<(), Error> {
+    if linked_accounts.len() > MAX_LINKED_ACCOUNTS {
+        return Err(Error::TooManyLinkedAccounts);
+    }
+    let platforms = active_platforms(e);
+    let mut seen: Vec<Symbol> = Vec::new(e);
+    for account in linked_accounts.iter() {
+        if !platforms.contains(&account.platform) {
+            return Err(Error::PlatformNotAllowed);
+        }
+        if seen.contains(&account.platform) {
+            return Err(Error::DuplicatePlatform);
+        }
+        if account.handle.is_empty() || account.handle.len() > MAX_LINKED_HANDLE_LENGTH {
+            return Err(Error::InvalidLinkedHandle);
+        }
+        seen.push_back(account.platform);
+    }
+    Ok(())
+}
+
+/// Validates the user-supplied fields of a profile
+pub fn validate_profile_fields(
+    e: &Env,
+    display_name: &String,
+    country_code: &Option<Symbol>,
+    linked_accounts: &Vec<LinkedAccount>,
+) -> Result<(), Error> {
+    validate_display_name(display_name)?;
+    if let Some(code) = country_code {
+        validate_country_code(e, code)?;
+    }
+    validate_linked_accounts(e, linked_accounts)
+}
+
+/// Validates evidence being attached to a claim that already has `existing` entries
+pub fn validate_claim_evidence(e: &Env, existing: u32, evidence: &Vec<ClaimEvidence>) -> Result<(), Error> {
+    if existing.saturating_add(evidence.len()) > MAX_CLAIM_EVIDENCE {
+        return Err(Error::TooMuchEvidence);
+    }
+    for entry in evidence.iter() {
+        validate_metadata_uri(e, &entry.uri)?;
+    }
+    Ok(())
+}
+
+/// Validates the validity window of a new claim: it must not be empty or
+/// already over
+pub fn validate_validity_window(e: &Env, window: &ValidityWindow) -> Result<(), Error> {
+    if let Some(until) = window.valid_until {
+        if until <= e.ledger().timestamp() || window.valid_from.is_some_and(|from| from >= until) {
+            return Err(Error::InvalidValidityWindow);
+        }
+    }
+    Ok(())
+}
+
+/// Message an email verifier signs to attest that `owner` controls the email
+/// behind `email_hash`: the XDR of `(contract, owner, email_hash, expiry, nonce)`.
+/// Including the contract address keeps attestations from being replayed on
+/// another deployment.
+pub fn email_attestation_message(
+    e: &Env,
+    owner: &Address,
+    email_hash: &BytesN<32>,
+    expiry: u64,
+    nonce: u64,
+) -> Bytes {
+    (e.current_contract_address(), owner.clone(), email_hash.clone(), expiry, nonce).to_xdr(e)
+}
+
+/// Maximum number of recovery guardians per profile
+pub const MAX_GUARDIANS: u32 = 10;
+
+/// Time between enough guardians approving a recovery and the recovery
+/// taking effect, during which the owner can cancel it
+pub const RECOVERY_DELAY: u64 = 3 * 24 * 60 * 60; // 3 days
+
+/// Validates a guardian list and recovery threshold. An empty list with a
+/// threshold of 0 turns recovery off.
+pub fn validate_guardians(owner: &Address, guardians: &Vec<Address>, threshold: u32) -> Result<(), Error> {
+    if guardians.len() > MAX_GUARDIANS || threshold > guardians.len() {
+        return Err(Error::InvalidGuardians);
+    }
+    if threshold == 0 && !guardians.is_empty() {
+        return Err(Error::InvalidGuardians);
+    }
+    for (i, guardian) in guardians.iter().enumerate() {
+        if guardian == *owner || guardians.first_index_of(&guardian) != Some(i as u32) {
+            return Err(Error::InvalidGuardians);
+        }
+    }
+    Ok(())
+}
+
+/// Minimum length of a handle
+pub const MIN_HANDLE_LENGTH: u32 = 3;
+
+/// Maximum length of a handle
+pub const MAX_HANDLE_LENGTH: u32 = 20;
+
+/// Time a released handle stays reserved for its previous owner
+pub const HANDLE_COOLDOWN: u64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Handles no profile can claim
+const RESERVED_HANDLES: [&str; 10] = [
+    "admin", "administrator", "help", "moderator", "offerhub", "offer_hub", "official",
+    "root", "support", "system",
+];
+
+/// Validates a handle and returns it as a symbol. A handle is 3 to 20
+/// lowercase letters, digits or underscores, starting with a letter.
+pub fn validate_handle(e: &Env, handle: &String) -> Result<Symbol, Error> {
+    let len = handle.len();
+    if !(MIN_HANDLE_LENGTH..=MAX_HANDLE_LENGTH).contains(&len) {
+        return Err(Error::InvalidHandle);
+    }
+    let mut buf = [0u8; MAX_HANDLE_LENGTH as usize];
+    handle.copy_into_slice(&mut buf[..len as usize]);
+    let handle = &buf[..len as usize];
+
+    if !handle[0].is_ascii_lowercase()
+        || !handle
+            .iter()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'_')
+    {
+        return Err(Error::InvalidHandle);
+    }
+    // Only ASCII was accepted above
+    let handle = core::str::from_utf8(handle).map_err(|_| Error::InvalidHandle)?;
+    if RESERVED_HANDLES.contains(&handle) {
+        return Err(Error::HandleReserved);
+    }
+    Ok(Symbol::new(e, handle))
+}
+
+/// Maximum length of a DID string
+pub const MAX_DID_LENGTH: u32 = 256;
+
+/// DID methods accepted until the admin sets an allowlist
+pub fn default_did_methods(e: &Env) -> Vec<String> {
+    let mut methods = Vec::new(e);
+    methods.push_back(String::from_str(e, "stellar"));
+    methods.push_back(String::from_str(e, "key"));
+    methods.push_back(String::from_str(e, "web"));
+    methods
+}
+
+/// Get the DID method allowlist currently in effect
+pub fn active_did_methods(e: &Env) -> Vec<String> {
+    get_did_methods(e).unwrap_or_else(|| default_did_methods(e))
+}
+
+/// A DID method name is one or more lowercase letters or digits
+fn is_valid_did_method(method: &[u8]) -> bool {
+    !method.is_empty() && method.iter().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+/// Validates a DID method name for the method allowlist
+pub fn validate_did_method(method: &String) -> Result<(), Error> {
+    let len = method.len() as usize;
+    if len == 0 || len > MAX_DID_LENGTH as usize {
+        return Err(Error::InvalidDid);
+    }
+    let mut buf = [0u8; MAX_DID_LENGTH as usize];
+    method.copy_into_slice(&mut buf[..len]);
+    if !is_valid_did_method(&buf[..len]) {
+        return Err(Error::InvalidDid);
+    }
+    Ok(())
+}
+
+/// Validates a DID against the W3C DID syntax and the allowed methods
+///
+/// did                = "did:" method-name ":" method-specific-id
+/// method-name        = 1*( %x61-7A / DIGIT )
+/// method-specific-id = *( *idchar ":" ) 1*idchar
+/// idchar             = ALPHA / DIGIT / "." / "-" / "_" / pct-encoded
+pub fn validate_did(e: &Env, did: &String, allowed_methods: &Vec<String>) -> Result<(), Error> {
+    let len = did.len() as usize;
+    if len == 0 || len > MAX_DID_LENGTH as usize {
+        return Err(Error::InvalidDid);
+    }
+    let mut buf = [0u8; MAX_DID_LENGTH as usize];
+    did.copy_into_slice(&mut buf[..len]);
+    let did = &buf[..len];
+
+    let rest = did.strip_prefix(b"did:").ok_or(Error::InvalidDid)?;
+    let colon = rest.iter().position(|c| *c == b':').ok_or(Error::InvalidDid)?;
+    let (method, id) = (&rest[..colon], &rest[colon + 1..]);
+
+    if !is_valid_did_method(method) {
+        return Err(Error::InvalidDid);
+    }
+
+    // The method-specific id may contain colons but must not end with one
+    if id.is_empty() || id[id.len() - 1] == b':' {
+        return Err(Error::InvalidDid);
+    }
+    let mut i = 0;
+    while i < id.len() {
+        match id[i] {
+            c if c.is_ascii_alphanumeric() || matches!(c, b'.' | b'-' | b'_' | b':') => i += 1,
+            b'%' if i + 2 < id.len()
+                && id[i + 1].is_ascii_hexdigit()
+                && id[i + 2].is_ascii_hexdigit() =>
+            {
+                i += 3
+            }
+            _ => return Err(Error::InvalidDid),
+        }
+    }
+
+    let method = String::from_bytes(e, method);
+    if !allowed_methods.contains(&method) {
+        return Err(Error::DidMethodNotAllowed);
+    }
+    Ok(())
+}
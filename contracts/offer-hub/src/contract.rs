@@ -1,96 +1,382 @@
-#![allow(deprecated)]
-
-use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, String, Vec, Symbol};
-use crate::types::{Claim, ClaimStatus, Profile, LinkedAccount};
+use soroban_sdk::{contract, contractimpl, xdr::ToXdr, Address, BytesN, Env, String, Vec, Symbol};
+use crate::types::{
+    Claim, ClaimEvidence, ClaimPage, ClaimStatus, HandleRecord, Profile, ProfileHistoryEntry, ProfileHistoryPage, ProfileStatus,
+    LinkedAccount, PauseScope,
+    RecoveryRequest, RevocationReason, ReputationBreakdown, ScoringConfig, ValidityWindow, WeightedReputation,
+};
 use crate::errors::Error;
-use crate::auth::validate_metadata_uri;
+use crate::events::{
+    AddressRotatedEvent, AdminChangedEvent, AdminProposedEvent, ClaimAddedEvent,
+    ClaimApprovedEvent, ClaimEvidenceAddedEvent, ClaimRejectedEvent, ClaimRevokedEvent, ContractPausedEvent,
+    ContractUnpausedEvent, ContractUpgradedEvent, DidLinkedEvent, DidMethodsUpdatedEvent,
+    DidUnlinkedEvent, EmailVerifiedEvent, EmailVerifierKeyUpdatedEvent, GuardiansUpdatedEvent,
+    HandleClaimedEvent, HandleReleasedEvent, LinkedAccountVerifiedEvent, MigrationCompletedEvent,
+    PlatformsUpdatedEvent, ProfileDeactivatedEvent, ProfileDeletedEvent, ProfileReactivatedEvent,
+    ProfileRegisteredEvent, ProfileUpdatedEvent, RecoveryApprovedEvent, RecoveryCancelledEvent,
+    ScoringConfigUpdatedEvent, UriSchemesUpdatedEvent, VerifierUpdatedEvent,
+    WeightedReputationRefreshedEvent,
+};
+use crate::migration::{migrate_v1_claims, migrate_v2_proof_hashes, CURRENT_SCHEMA_VERSION};
+use crate::scoring::{
+    active_scoring_config, current_epoch, derived_status, previous_weighted_score, reputation_breakdown,
+    reputation_score, validate_scoring_config, weighted_reputation,
+};
+use crate::auth::{
+    active_did_methods, active_platforms, active_uri_schemes, email_attestation_message, is_active_profile,
+    require_active_profile, require_admin, require_not_paused, validate_claim_evidence,
+    validate_did, validate_did_method, validate_validity_window,
+    validate_guardians, validate_handle, validate_metadata_uri, validate_profile_fields,
+    validate_uri_scheme, HANDLE_COOLDOWN, RECOVERY_DELAY,
+};
 use crate::storage::{
-    add_user_claim, add_issuer_claim, get_claim, get_next_claim_id, 
-    get_profile, get_user_claims, get_issuer_claims, has_profile,
+    add_user_claim, add_issuer_claim, get_claim, get_next_claim_id,
+    add_proof_hash_claim, get_proof_hash_claim_count, get_proof_hash_claims_range,
+    get_receiver_proof_hash_claim, set_receiver_proof_hash_claim, registered_address,
+    get_profile, has_profile,
     increment_next_claim_id, set_claim, set_profile,
+    get_revocation_reason, set_revocation_reason,
+    get_user_claim_count, get_user_claims_range,
+    get_issuer_claim_count, get_issuer_claims_range,
+    get_admin, set_admin, get_pending_admin, set_pending_admin, remove_pending_admin,
+    get_schema_version, set_schema_version, is_paused, set_paused, set_scoring_config,
+    get_did_owner, set_did_owner, remove_did_owner, set_did_methods,
+    remove_profile, get_rotated_to, set_rotated_to, resolve_address,
+    move_user_claims, move_issuer_claims, get_recovery, set_recovery, remove_recovery,
+    get_handle_record, set_handle_record, set_platforms, set_uri_schemes, is_verifier, set_verifier,
+    is_email_verifier_key, set_email_verifier_key, get_email_nonce, set_email_nonce,
+    get_profile_history_entry, set_profile_history_entry,
+    get_weighted_reputation, set_weighted_reputation,
 };
 
+/// Maximum number of claims returned by a single page query
+pub const MAX_PAGE_SIZE: u32 = 50;
+
 #[contract]
 pub struct OfferHub;
 
 #[contractimpl]
 impl OfferHub {
+    /// Initialize the contract with its admin
+    pub fn __constructor(e: Env, admin: Address) {
+        set_admin(&e, &admin);
+        set_schema_version(&e, CURRENT_SCHEMA_VERSION);
+    }
+
+    // ==========================================================================
+    // Admin
+    // ==========================================================================
+
+    /// Propose a new admin. The proposed address must call `accept_admin` to take over.
+    pub fn propose_admin(e: Env, new_admin: Address) {
+        let admin = require_admin(&e);
+        set_pending_admin(&e, &new_admin);
+
+        // Emit event
+        AdminProposedEvent {
+            admin,
+            pending_admin: new_admin,
+        }.publish(&e);
+    }
+
+    /// Accept a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(e: Env) -> Result<(), Error> {
+        let new_admin = get_pending_admin(&e).ok_or(Error::NoPendingAdmin)?;
+        new_admin.require_auth();
+
+        let previous_admin = get_admin(&e);
+        set_admin(&e, &new_admin);
+        remove_pending_admin(&e);
+
+        // Emit event
+        AdminChangedEvent {
+            previous_admin,
+            new_admin,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Replace the contract code. Run `migrate` afterwards if the new code
+    /// bumps the schema version.
+    pub fn upgrade(e: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&e);
+        e.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        // Emit event
+        ContractUpgradedEvent { new_wasm_hash }.publish(&e);
+    }
+
+    /// Migrate stored records from `from_version` to the current schema version.
+    /// Records are rewritten in batches; call repeatedly until it returns `true`.
+    pub fn migrate(e: Env, from_version: u32) -> Result<bool, Error> {
+        require_admin(&e);
+
+        let version = get_schema_version(&e);
+        if from_version != version {
+            return Err(Error::SchemaVersionMismatch);
+        }
+        if version == CURRENT_SCHEMA_VERSION {
+            return Ok(true);
+        }
+
+        let done = match version {
+            1 => migrate_v1_claims(&e),
+            2 => migrate_v2_proof_hashes(&e),
+            _ => return Err(Error::SchemaVersionMismatch),
+        };
+
+        if done {
+            set_schema_version(&e, CURRENT_SCHEMA_VERSION);
+
+            // Emit event
+            MigrationCompletedEvent {
+                from_version,
+                to_version: CURRENT_SCHEMA_VERSION,
+            }.publish(&e);
+        }
+
+        Ok(done)
+    }
+
+    /// Pause every state-changing entry point except admin ones
+    pub fn pause(e: Env) {
+        Self::pause_scope(e, PauseScope::All);
+    }
+
+    /// Lift a pause set with `pause`. Scopes paused separately stay paused.
+    pub fn unpause(e: Env) {
+        Self::unpause_scope(e, PauseScope::All);
+    }
+
+    /// Pause one group of entry points, e.g. claims only
+    pub fn pause_scope(e: Env, scope: PauseScope) {
+        require_admin(&e);
+        set_paused(&e, &scope, true);
+
+        // Emit event
+        ContractPausedEvent { scope }.publish(&e);
+    }
+
+    /// Unpause one group of entry points
+    pub fn unpause_scope(e: Env, scope: PauseScope) {
+        require_admin(&e);
+        set_paused(&e, &scope, false);
+
+        // Emit event
+        ContractUnpausedEvent { scope }.publish(&e);
+    }
+
+    /// Replace the scoring table used by `get_reputation_score`
+    pub fn set_scoring_config(e: Env, config: ScoringConfig) -> Result<(), Error> {
+        require_admin(&e);
+        validate_scoring_config(&config)?;
+        set_scoring_config(&e, &config);
+
+        // Emit event
+        ScoringConfigUpdatedEvent { config }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Replace the allowlist of DID methods accepted by `link_did`
+    pub fn set_did_methods(e: Env, methods: Vec<String>) -> Result<(), Error> {
+        require_admin(&e);
+        for method in methods.iter() {
+            validate_did_method(&method)?;
+        }
+        set_did_methods(&e, &methods);
+
+        // Emit event
+        DidMethodsUpdatedEvent { methods }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Approve or remove an address allowed to verify linked accounts
+    pub fn set_verifier(e: Env, verifier: Address, approved: bool) {
+        require_admin(&e);
+        set_verifier(&e, &verifier, approved);
+
+        // Emit event
+        VerifierUpdatedEvent { verifier, approved }.publish(&e);
+    }
+
+    /// Register or remove an ed25519 public key allowed to sign email attestations
+    pub fn set_email_verifier_key(e: Env, public_key: BytesN<32>, approved: bool) {
+        require_admin(&e);
+        set_email_verifier_key(&e, &public_key, approved);
+
+        // Emit event
+        EmailVerifierKeyUpdatedEvent { public_key, approved }.publish(&e);
+    }
+
+    /// Replace the allowlist of URI schemes accepted for profile metadata
+    pub fn set_uri_schemes(e: Env, schemes: Vec<String>) -> Result<(), Error> {
+        require_admin(&e);
+        for scheme in schemes.iter() {
+            validate_uri_scheme(&scheme)?;
+        }
+        set_uri_schemes(&e, &schemes);
+
+        // Emit event
+        UriSchemesUpdatedEvent { schemes }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Replace the allowlist of platforms accepted in linked accounts
+    pub fn set_platforms(e: Env, platforms: Vec<Symbol>) {
+        require_admin(&e);
+        set_platforms(&e, &platforms);
+
+        // Emit event
+        PlatformsUpdatedEvent { platforms }.publish(&e);
+    }
+
+    // ==========================================================================
+    // Profiles & Claims
+    // ==========================================================================
+
     /// Register a new profile for the caller
+    #[allow(clippy::too_many_arguments)]
     pub fn register_profile(
         e: Env,
         owner: Address,
         metadata_uri: String,
+        metadata_hash: Option<BytesN<32>>,
         display_name: String,
         country_code: Option<Symbol>, 
         email_hash: Option<BytesN<32>>,
         linked_accounts: Vec<LinkedAccount>
     ) -> Result<(), Error> {
         owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
 
         // Validate inputs
-        validate_metadata_uri(&metadata_uri)?;
+        validate_metadata_uri(&e, &metadata_uri)?;
+        validate_profile_fields(&e, &display_name, &country_code, &linked_accounts)?;
 
-        if has_profile(&e, &owner) {
-            return Err(Error::ProfileAlreadyExists);
+        if let Some(existing) = get_profile(&e, &owner) {
+            // Deleted profiles stay as tombstones so the address can't be reused
+            return Err(match existing.status {
+                ProfileStatus::Deleted => Error::ProfileDeleted,
+                _ => Error::ProfileAlreadyExists,
+            });
+        }
+        if get_rotated_to(&e, &owner).is_some() {
+            return Err(Error::AddressRotated);
         }
-        
-        // country_code is already Option<Symbol> (or similar) if we change signature
-        // Let's change signature to use Symbol for country_code
 
         let profile = Profile {
             owner: owner.clone(),
             metadata_uri: metadata_uri.clone(),
             did: None,
             display_name,
-            country_code, // Use directly
+            country_code,
             email_hash,
-            linked_accounts,
+            linked_accounts: keep_verifications(&e, linked_accounts, &Vec::new(&e)),
             joined_at: e.ledger().timestamp(),
+            guardians: Vec::new(&e),
+            recovery_threshold: 0,
+            handle: None,
+            metadata_hash,
+            status: ProfileStatus::Active,
+            email_verified: false,
+            version: 1,
+            updated_at: e.ledger().timestamp(),
+            registered_as: owner.clone(),
         };
 
         set_profile(&e, &owner, &profile);
         
         // Emit event
-        e.events().publish((symbol_short!("prof_reg"),), (owner, metadata_uri));
+        ProfileRegisteredEvent { owner, metadata_uri }.publish(&e);
         
         Ok(())
     }
 
     /// Update profile data
+    #[allow(clippy::too_many_arguments)]
     pub fn update_profile_data(
         e: Env,
         owner: Address,
         display_name: String,
         metadata_uri: String,
+        metadata_hash: Option<BytesN<32>>,
         country_code: Option<Symbol>,
         email_hash: Option<BytesN<32>>,
         linked_accounts: Vec<LinkedAccount>
     ) -> Result<(), Error> {
         owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
         
-        let mut profile = get_profile(&e, &owner).ok_or(Error::ProfileNotFound)?;
+        let mut profile = require_active_profile(&e, &owner)?;
         
-        validate_metadata_uri(&metadata_uri)?;
+        validate_metadata_uri(&e, &metadata_uri)?;
+        validate_profile_fields(&e, &display_name, &country_code, &linked_accounts)?;
 
         profile.display_name = display_name;
         profile.metadata_uri = metadata_uri;
+        profile.metadata_hash = metadata_hash;
         profile.country_code = country_code;
+        // A verified email stays verified only while the hash is unchanged
+        if profile.email_hash != email_hash {
+            profile.email_verified = false;
+        }
         profile.email_hash = email_hash;
-        profile.linked_accounts = linked_accounts;
+        profile.linked_accounts = keep_verifications(&e, linked_accounts, &profile.linked_accounts);
+
+        save_profile(&e, &owner, profile.clone());
+
+        // Emit event
+        ProfileUpdatedEvent {
+            owner,
+            metadata_uri: profile.metadata_uri,
+        }.publish(&e);
 
-        set_profile(&e, &owner, &profile);
         Ok(())
     }
 
-    /// Add a new claim to another user
+    /// Add a new claim to another user, optionally pointing at off-chain
+    /// details and evidence documents. A proof hash can only back one pending
+    /// or approved claim per receiver. Claims only count towards reputation
+    /// inside `validity` when it is set.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_claim(
         e: Env,
         issuer: Address,
         receiver: Address,
         claim_type: String,
         proof_hash: BytesN<32>,
+        metadata_uri: Option<String>,
+        evidence: Vec<ClaimEvidence>,
+        validity: Option<ValidityWindow>,
     ) -> Result<u64, Error> {
         issuer.require_auth();
+        require_not_paused(&e, PauseScope::Claims)?;
+
+        let validity = validity.unwrap_or(ValidityWindow { valid_from: None, valid_until: None });
+        validate_validity_window(&e, &validity)?;
+        if let Some(uri) = &metadata_uri {
+            validate_metadata_uri(&e, uri)?;
+        }
+        validate_claim_evidence(&e, 0, &evidence)?;
+
+        if get_rotated_to(&e, &issuer).is_some() {
+            return Err(Error::AddressRotated);
+        }
+        // Claims sent to a rotated address go to where the identity lives now
+        let receiver = resolve_address(&e, &receiver);
+        if issuer == receiver {
+            return Err(Error::SelfClaimNotAllowed);
+        }
+        // Keyed by the address the receiver registered with, so rotating
+        // does not free a proof hash
+        let receiver_key = registered_address(&e, &receiver);
+        let previous = get_receiver_proof_hash_claim(&e, &proof_hash, &receiver_key).and_then(|id| get_claim(&e, id));
+        if previous.is_some_and(|claim| matches!(claim.status, ClaimStatus::Pending | ClaimStatus::Approved)) {
+            return Err(Error::DuplicateProofHash);
+        }
 
         let claim_id = increment_next_claim_id(&e);
 
@@ -100,63 +386,749 @@ impl OfferHub {
             receiver: receiver.clone(),
             claim_type: claim_type.clone(),
             proof_hash,
-            status: ClaimStatus::Approved,
+            status: ClaimStatus::Pending,
+            revoked_at: None,
+            metadata_uri,
+            evidence,
+            valid_from: validity.valid_from,
+            valid_until: validity.valid_until,
+            issued_at: Some(e.ledger().timestamp()),
         };
 
         set_claim(&e, claim_id, &claim);
         add_user_claim(&e, &receiver, claim_id);
         add_issuer_claim(&e, &issuer, claim_id);
+        add_proof_hash_claim(&e, &claim.proof_hash, claim_id);
+        set_receiver_proof_hash_claim(&e, &claim.proof_hash, &receiver_key, claim_id);
 
         // Emit event
-        e.events().publish((symbol_short!("claim_add"),), (claim_id, issuer, receiver, claim_type));
+        ClaimAddedEvent {
+            claim_id,
+            issuer,
+            receiver,
+            claim_type,
+        }.publish(&e);
 
         Ok(claim_id)
     }
 
-    /// Link a DID to the caller's profile - REMOVED
-    // This function is no longer needed for the MVP
-    // pub fn link_did(...)
+    /// Attach more evidence to a claim. Either the issuer or the receiver can
+    /// attach evidence, up to `MAX_CLAIM_EVIDENCE` entries per claim.
+    pub fn add_claim_evidence(
+        e: Env,
+        caller: Address,
+        claim_id: u64,
+        evidence: Vec<ClaimEvidence>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        require_not_paused(&e, PauseScope::Claims)?;
+
+        let mut claim = get_claim(&e, claim_id).ok_or(Error::ClaimNotFound)?;
+
+        if caller != claim.issuer && caller != claim.receiver {
+            return Err(Error::Unauthorized);
+        }
+        if claim.status == ClaimStatus::Revoked {
+            return Err(Error::ClaimAlreadyRevoked);
+        }
+        validate_claim_evidence(&e, claim.evidence.len(), &evidence)?;
+
+        claim.evidence.append(&evidence);
+        set_claim(&e, claim_id, &claim);
+
+        // Emit event
+        ClaimEvidenceAddedEvent {
+            claim_id,
+            added_by: caller,
+            evidence,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Approve a pending claim. Only the receiver can approve.
+    pub fn approve_claim(e: Env, receiver: Address, claim_id: u64) -> Result<(), Error> {
+        receiver.require_auth();
+        require_not_paused(&e, PauseScope::Claims)?;
+
+        let mut claim = get_claim(&e, claim_id).ok_or(Error::ClaimNotFound)?;
+
+        if claim.receiver != receiver {
+            return Err(Error::Unauthorized);
+        }
+        if claim.status != ClaimStatus::Pending {
+            return Err(Error::ClaimAlreadyProcessed);
+        }
+
+        claim.status = ClaimStatus::Approved;
+        set_claim(&e, claim_id, &claim);
+
+        // Emit event
+        ClaimApprovedEvent {
+            claim_id,
+            issuer: claim.issuer,
+            receiver: claim.receiver,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Reject a pending claim. Only the receiver can reject.
+    pub fn reject_claim(e: Env, receiver: Address, claim_id: u64) -> Result<(), Error> {
+        receiver.require_auth();
+        require_not_paused(&e, PauseScope::Claims)?;
+
+        let mut claim = get_claim(&e, claim_id).ok_or(Error::ClaimNotFound)?;
+
+        if claim.receiver != receiver {
+            return Err(Error::Unauthorized);
+        }
+        if claim.status != ClaimStatus::Pending {
+            return Err(Error::ClaimAlreadyProcessed);
+        }
+
+        claim.status = ClaimStatus::Rejected;
+        set_claim(&e, claim_id, &claim);
+
+        // Emit event
+        ClaimRejectedEvent {
+            claim_id,
+            issuer: claim.issuer,
+            receiver: claim.receiver,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Revoke a pending or approved claim. Only the original issuer can revoke.
+    pub fn revoke_claim(
+        e: Env,
+        issuer: Address,
+        claim_id: u64,
+        reason: RevocationReason,
+    ) -> Result<(), Error> {
+        issuer.require_auth();
+        require_not_paused(&e, PauseScope::Claims)?;
+
+        let mut claim = get_claim(&e, claim_id).ok_or(Error::ClaimNotFound)?;
+
+        if claim.issuer != issuer {
+            return Err(Error::NotClaimIssuer);
+        }
+        match claim.status {
+            ClaimStatus::Pending | ClaimStatus::Approved | ClaimStatus::Expired => {}
+            ClaimStatus::Revoked => return Err(Error::ClaimAlreadyRevoked),
+            ClaimStatus::Rejected => return Err(Error::ClaimAlreadyProcessed),
+        }
+
+        claim.status = ClaimStatus::Revoked;
+        claim.revoked_at = Some(e.ledger().timestamp());
+        set_claim(&e, claim_id, &claim);
+        set_revocation_reason(&e, claim_id, &reason);
+
+        // Emit event
+        ClaimRevokedEvent {
+            claim_id,
+            issuer: claim.issuer,
+            receiver: claim.receiver,
+            reason,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Link a DID to the caller's profile, replacing any DID linked before.
+    /// A DID can only be linked to one profile at a time.
+    pub fn link_did(e: Env, owner: Address, did: String) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+
+        validate_did(&e, &did, &active_did_methods(&e))?;
+
+        if let Some(linked_owner) = get_did_owner(&e, &did) {
+            if linked_owner != owner {
+                return Err(Error::DidAlreadyLinked);
+            }
+        }
+
+        if let Some(old_did) = profile.did.as_ref() {
+            remove_did_owner(&e, old_did);
+        }
+        profile.did = Some(did.clone());
+        save_profile(&e, &owner, profile);
+        set_did_owner(&e, &did, &owner);
+
+        // Emit event
+        DidLinkedEvent { owner, did }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Unlink the DID from the caller's profile
+    pub fn unlink_did(e: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+        let did = profile.did.take().ok_or(Error::DidNotLinked)?;
+
+        save_profile(&e, &owner, profile);
+        remove_did_owner(&e, &did);
+
+        // Emit event
+        DidUnlinkedEvent { owner, did }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Attest that the owner of a profile controls one of its linked
+    /// accounts. Only approved verifiers can call it, e.g. an oracle that
+    /// checked a gist published from the account.
+    pub fn verify_linked_account(
+        e: Env,
+        verifier: Address,
+        owner: Address,
+        platform: Symbol,
+        handle: String,
+        proof_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        verifier.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        if !is_verifier(&e, &verifier) {
+            return Err(Error::NotVerifier);
+        }
+        let mut profile = require_active_profile(&e, &owner)?;
+
+        let index = profile
+            .linked_accounts
+            .iter()
+            .position(|account| account.platform == platform && account.handle == handle)
+            .ok_or(Error::LinkedAccountNotFound)? as u32;
+        let mut account = profile.linked_accounts.get(index).unwrap();
+        account.verified_by = Some(verifier.clone());
+        account.verified_at = Some(e.ledger().timestamp());
+        account.proof_hash = Some(proof_hash.clone());
+        profile.linked_accounts.set(index, account);
+        save_profile(&e, &owner, profile);
+
+        // Emit event
+        LinkedAccountVerifiedEvent {
+            owner,
+            verifier,
+            platform,
+            handle,
+            proof_hash,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Set the caller's email hash from an attestation signed by a registered
+    /// email verifier key over `email_attestation_message`. `nonce` must be
+    /// the owner's next nonce (see `get_email_nonce`). The key, expiry and
+    /// nonce are checked first and reported as errors; an invalid signature
+    /// aborts the transaction with a host error instead.
+    pub fn verify_email(
+        e: Env,
+        owner: Address,
+        email_hash: BytesN<32>,
+        expiry: u64,
+        nonce: u64,
+        verifier_key: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+
+        if !is_email_verifier_key(&e, &verifier_key) {
+            return Err(Error::UnknownVerifierKey);
+        }
+        if e.ledger().timestamp() > expiry {
+            return Err(Error::AttestationExpired);
+        }
+        if nonce != get_email_nonce(&e, &owner) {
+            return Err(Error::InvalidNonce);
+        }
+
+        let message = email_attestation_message(&e, &owner, &email_hash, expiry, nonce);
+        e.crypto().ed25519_verify(&verifier_key, &message, &signature);
+
+        set_email_nonce(&e, &owner, nonce + 1);
+        profile.email_hash = Some(email_hash.clone());
+        profile.email_verified = true;
+        save_profile(&e, &owner, profile);
+
+        // Emit event
+        EmailVerifiedEvent {
+            owner,
+            email_hash,
+            verifier_key,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Deactivate the caller's profile. It is hidden from getters and scoring
+    /// until `reactivate_profile` is called; nothing is erased.
+    pub fn deactivate_profile(e: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+        profile.status = ProfileStatus::Deactivated;
+        save_profile(&e, &owner, profile);
+
+        // Emit event
+        ProfileDeactivatedEvent { owner }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Restore a profile deactivated with `deactivate_profile`
+    pub fn reactivate_profile(e: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = get_profile(&e, &owner).ok_or(Error::ProfileNotFound)?;
+        if profile.status != ProfileStatus::Deactivated {
+            return Err(Error::ProfileNotDeactivated);
+        }
+        profile.status = ProfileStatus::Active;
+        save_profile(&e, &owner, profile);
+
+        // Emit event
+        ProfileReactivatedEvent { owner }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Permanently erase the personal data of the caller's profile. The DID
+    /// and handle are released, and a tombstone keeps the address from being
+    /// registered again. Claims stay attributed to the address.
+    pub fn delete_profile(e: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let profile = get_profile(&e, &owner).ok_or(Error::ProfileNotFound)?;
+        if profile.status == ProfileStatus::Deleted {
+            return Err(Error::ProfileDeleted);
+        }
+
+        if let Some(did) = profile.did.as_ref() {
+            remove_did_owner(&e, did);
+        }
+        if let Some(handle) = profile.handle.as_ref() {
+            release_handle(&e, &owner, handle);
+        }
+        remove_recovery(&e, &owner);
+
+        let tombstone = Profile {
+            owner: owner.clone(),
+            metadata_uri: String::from_str(&e, ""),
+            did: None,
+            display_name: String::from_str(&e, ""),
+            country_code: None,
+            email_hash: None,
+            linked_accounts: Vec::new(&e),
+            joined_at: profile.joined_at,
+            guardians: Vec::new(&e),
+            recovery_threshold: 0,
+            handle: None,
+            metadata_hash: None,
+            status: ProfileStatus::Deleted,
+            email_verified: false,
+            version: profile.version,
+            updated_at: profile.updated_at,
+            registered_as: profile.registered_as,
+        };
+        save_profile(&e, &owner, tombstone);
+
+        // Emit event
+        ProfileDeletedEvent { owner }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Claim a unique handle for the caller's profile. A profile that already
+    /// has a handle is renamed and its old handle released.
+    pub fn claim_handle(e: Env, owner: Address, handle: String) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+        let handle = validate_handle(&e, &handle)?;
+        if profile.handle.as_ref() == Some(&handle) {
+            return Ok(());
+        }
+
+        if let Some(record) = get_handle_record(&e, &handle) {
+            let released_at = record.released_at.ok_or(Error::HandleTaken)?;
+            let cooling_down = e.ledger().timestamp() < released_at + HANDLE_COOLDOWN;
+            if cooling_down && resolve_address(&e, &record.owner) != owner {
+                return Err(Error::HandleCoolingDown);
+            }
+        }
+
+        let previous_handle = profile.handle.take();
+        if let Some(previous) = previous_handle.as_ref() {
+            release_handle(&e, &owner, previous);
+        }
+        set_handle_record(&e, &handle, &HandleRecord {
+            owner: owner.clone(),
+            released_at: None,
+        });
+        profile.handle = Some(handle.clone());
+        save_profile(&e, &owner, profile);
+
+        // Emit event
+        HandleClaimedEvent {
+            owner,
+            handle,
+            previous_handle,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Release the caller's handle. It stays reserved for the caller during
+    /// `HANDLE_COOLDOWN`.
+    pub fn release_handle(e: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+        let handle = profile.handle.take().ok_or(Error::HandleNotSet)?;
+
+        release_handle(&e, &owner, &handle);
+        save_profile(&e, &owner, profile);
+
+        // Emit event
+        HandleReleasedEvent { owner, handle }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Move a profile to a new address, e.g. after a wallet change. Both
+    /// addresses must authorize. The profile, the claims received and issued
+    /// and the linked DID move to `new_owner`; `old_owner` keeps a record of
+    /// where the identity went.
+    pub fn rotate_address(e: Env, old_owner: Address, new_owner: Address) -> Result<(), Error> {
+        old_owner.require_auth();
+        new_owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let profile = require_active_profile(&e, &old_owner)?;
+        move_identity(&e, old_owner, profile, new_owner)
+    }
+
+    /// Set the guardians that can recover the caller's profile and how many
+    /// of them must approve. Cancels any pending recovery.
+    pub fn set_guardians(
+        e: Env,
+        owner: Address,
+        guardians: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let mut profile = require_active_profile(&e, &owner)?;
+        validate_guardians(&owner, &guardians, threshold)?;
+
+        profile.guardians = guardians.clone();
+        profile.recovery_threshold = threshold;
+        save_profile(&e, &owner, profile);
+        remove_recovery(&e, &owner);
+
+        // Emit event
+        GuardiansUpdatedEvent { owner, guardians, threshold }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Approve moving a profile to `new_owner`. Approvals are counted per
+    /// address, and a guardian backs one address at a time: approving another
+    /// address withdraws its earlier approval. Once enough guardians approved
+    /// the same address, the recovery can be completed after `RECOVERY_DELAY`.
+    pub fn approve_recovery(
+        e: Env,
+        guardian: Address,
+        owner: Address,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        guardian.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let profile = require_active_profile(&e, &owner)?;
+        if !profile.guardians.contains(&guardian) {
+            return Err(Error::NotGuardian);
+        }
+        let threshold = profile.recovery_threshold;
+
+        let mut target = RecoveryRequest {
+            new_owner: new_owner.clone(),
+            approvals: Vec::new(&e),
+            executable_at: None,
+        };
+        let mut requests = Vec::new(&e);
+        for mut request in get_recovery(&e, &owner).iter() {
+            if request.new_owner == new_owner {
+                target = request;
+                continue;
+            }
+            // Withdraw the guardian's approval of any other address
+            if let Some(index) = request.approvals.first_index_of(&guardian) {
+                request.approvals.remove(index);
+                if request.approvals.len() < threshold {
+                    request.executable_at = None;
+                }
+            }
+            if !request.approvals.is_empty() {
+                requests.push_back(request);
+            }
+        }
+
+        if !target.approvals.contains(&guardian) {
+            target.approvals.push_back(guardian.clone());
+        }
+        if target.executable_at.is_none() && target.approvals.len() >= threshold {
+            target.executable_at = Some(e.ledger().timestamp() + RECOVERY_DELAY);
+        }
+        requests.push_back(target.clone());
+        set_recovery(&e, &owner, &requests);
+
+        // Emit event
+        RecoveryApprovedEvent {
+            owner,
+            guardian,
+            new_owner,
+            executable_at: target.executable_at,
+        }.publish(&e);
+
+        Ok(())
+    }
+
+    /// Cancel every pending recovery of the caller's profile. Not pausable, so
+    /// an owner can always stop a recovery they didn't ask for.
+    pub fn cancel_recovery(e: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+
+        let requests = get_recovery(&e, &owner);
+        if requests.is_empty() {
+            return Err(Error::RecoveryNotFound);
+        }
+        remove_recovery(&e, &owner);
+
+        // Emit event
+        for request in requests.iter() {
+            RecoveryCancelledEvent {
+                owner: owner.clone(),
+                new_owner: request.new_owner,
+            }.publish(&e);
+        }
+
+        Ok(())
+    }
+
+    /// Complete the recovery to `new_owner` once enough guardians approved it
+    /// and its delay has passed, moving the profile like `rotate_address`.
+    /// Must be authorized by the new address.
+    pub fn complete_recovery(e: Env, owner: Address, new_owner: Address) -> Result<(), Error> {
+        new_owner.require_auth();
+        require_not_paused(&e, PauseScope::Profiles)?;
+
+        let requests = get_recovery(&e, &owner);
+        if requests.is_empty() {
+            return Err(Error::RecoveryNotFound);
+        }
+        let request = requests
+            .iter()
+            .find(|request| request.new_owner == new_owner)
+            .ok_or(Error::RecoveryTargetMismatch)?;
+
+        match request.executable_at {
+            Some(time) if e.ledger().timestamp() >= time => {}
+            _ => return Err(Error::RecoveryNotReady),
+        }
+
+        let profile = require_active_profile(&e, &owner)?;
+        move_identity(&e, owner, profile, new_owner)
+    }
+
+    /// Compute the issuer-weighted reputation of an account and store it for
+    /// the current epoch. Anyone can refresh any account; stored scores are
+    /// returned by `get_weighted_reputation` and used, from the next epoch on,
+    /// when weighting the claims the account issued.
+    pub fn refresh_weighted_reputation(e: Env, account: Address) -> Result<u32, Error> {
+        require_not_paused(&e, PauseScope::Claims)?;
+
+        let reputation = WeightedReputation {
+            score: weighted_reputation(&e, &account),
+            epoch: current_epoch(&e),
+            previous_score: previous_weighted_score(&e, &account),
+        };
+        set_weighted_reputation(&e, &account, &reputation);
+
+        // Emit event
+        WeightedReputationRefreshedEvent {
+            account,
+            score: reputation.score,
+            epoch: reputation.epoch,
+        }.publish(&e);
+
+        Ok(reputation.score)
+    }
 
     // ==========================================================================
     // Getters
     // ==========================================================================
 
-    /// Get profile by address
+    /// Get the contract admin
+    pub fn get_admin(e: Env) -> Address {
+        get_admin(&e)
+    }
+
+    /// Get the admin proposed by `propose_admin`, if any
+    pub fn get_pending_admin(e: Env) -> Option<Address> {
+        get_pending_admin(&e)
+    }
+
+    /// Whether the whole contract is paused
+    pub fn is_paused(e: Env) -> bool {
+        is_paused(&e, &PauseScope::All)
+    }
+
+    /// Whether the entry points in `scope` are paused, either on their own or
+    /// because the whole contract is paused
+    pub fn is_scope_paused(e: Env, scope: PauseScope) -> bool {
+        is_paused(&e, &PauseScope::All) || is_paused(&e, &scope)
+    }
+
+    /// Get the schema version of the stored records
+    pub fn get_schema_version(e: Env) -> u32 {
+        get_schema_version(&e)
+    }
+
+    /// Get profile by address. For an address that was rotated away, this is
+    /// the profile at its new address, whose `owner` tells where it moved.
+    /// Deactivated and deleted profiles are not returned.
     pub fn get_profile(e: Env, account: Address) -> Option<Profile> {
-        get_profile(&e, &account)
+        get_profile(&e, &resolve_address(&e, &account))
+            .filter(|profile| profile.status == ProfileStatus::Active)
+    }
+
+    /// Get the history entry of one version of a profile. Versions start at 1
+    /// on registration; every later change has an entry. The history follows
+    /// the profile across rotations, so any of its addresses can be passed.
+    /// Like the profile itself, it is hidden while deactivated or once deleted.
+    pub fn get_profile_version(e: Env, account: Address, version: u32) -> Option<ProfileHistoryEntry> {
+        let profile = Self::get_profile(e.clone(), account)?;
+        get_profile_history_entry(&e, &profile.registered_as, version)
+    }
+
+    /// Get a page of the history entries of a profile from version `cursor` on.
+    /// `limit` is capped at `MAX_PAGE_SIZE`. The history follows the profile
+    /// across rotations, so any of its addresses can be passed. Like the
+    /// profile itself, it is hidden while deactivated or once deleted.
+    pub fn get_profile_history(e: Env, account: Address, cursor: u32, limit: u32) -> ProfileHistoryPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let mut entries = Vec::new(&e);
+        let profile = match Self::get_profile(e.clone(), account) {
+            Some(profile) => profile,
+            None => return ProfileHistoryPage { entries, next_cursor: None },
+        };
+        // Version 1 is the registration, which has no entry
+        let start = cursor.max(2);
+        let end = start.saturating_add(limit).min(profile.version.saturating_add(1));
+        for version in start..end {
+            if let Some(entry) = get_profile_history_entry(&e, &profile.registered_as, version) {
+                entries.push_back(entry);
+            }
+        }
+        ProfileHistoryPage {
+            entries,
+            next_cursor: if end <= profile.version { Some(end) } else { None },
+        }
+    }
+
+    /// Get the lifecycle state of a profile, including deactivated and deleted ones
+    pub fn get_profile_status(e: Env, account: Address) -> Option<ProfileStatus> {
+        get_profile(&e, &account).map(|profile| profile.status)
+    }
+
+    /// Get the pending guardian recoveries of a profile, one per proposed address
+    pub fn get_recovery(e: Env, owner: Address) -> Vec<RecoveryRequest> {
+        get_recovery(&e, &owner)
+    }
+
+    /// Get the address a profile was rotated to, following every later rotation
+    pub fn get_rotated_address(e: Env, account: Address) -> Option<Address> {
+        get_rotated_to(&e, &account).map(|next| resolve_address(&e, &next))
     }
 
     /// Get claim by ID
     pub fn get_claim(e: Env, claim_id: u64) -> Option<Claim> {
-        get_claim(&e, claim_id)
+        load_claim(&e, claim_id)
     }
 
-    /// Get all claims received by a user
+    /// Get a page of the claims issued with a proof hash, oldest first, so a
+    /// verifier holding the original document can find them. Each receiver has
+    /// at most one pending or approved claim per proof hash.
+    /// `cursor` is the position to start from (0 for the first page) and
+    /// `limit` is capped at `MAX_PAGE_SIZE`.
+    pub fn get_claim_by_proof_hash(e: Env, proof_hash: BytesN<32>, cursor: u32, limit: u32) -> ClaimPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let total = get_proof_hash_claim_count(&e, &proof_hash);
+        let claim_ids = get_proof_hash_claims_range(&e, &proof_hash, cursor, limit);
+        load_claims_page(&e, claim_ids, cursor, total)
+    }
+
+    /// Get the reason a claim was revoked, if it was
+    pub fn get_revocation_reason(e: Env, claim_id: u64) -> Option<RevocationReason> {
+        get_revocation_reason(&e, claim_id)
+    }
+
+    /// Get the first `MAX_PAGE_SIZE` claims received by a user.
+    /// Deprecated: use `get_user_claims_page` to read every claim.
     pub fn get_user_claims(e: Env, account: Address) -> Vec<Claim> {
-        let claim_ids = get_user_claims(&e, &account);
-        let mut claims = Vec::new(&e);
-        
-        for id in claim_ids.iter() {
-             if let Some(claim) = get_claim(&e, id) {
-                 claims.push_back(claim);
-             }
-        }
-        
-        claims
+        Self::get_user_claims_page(e, account, 0, MAX_PAGE_SIZE).claims
     }
 
-    /// Get all claims issued by a user
+    /// Get the first `MAX_PAGE_SIZE` claims issued by a user.
+    /// Deprecated: use `get_issuer_claims_page` to read every claim.
     pub fn get_issuer_claims(e: Env, account: Address) -> Vec<Claim> {
-        let claim_ids = get_issuer_claims(&e, &account);
-        let mut claims = Vec::new(&e);
-        
-        for id in claim_ids.iter() {
-             if let Some(claim) = get_claim(&e, id) {
-                 claims.push_back(claim);
-             }
-        }
-        
-        claims
+        Self::get_issuer_claims_page(e, account, 0, MAX_PAGE_SIZE).claims
+    }
+
+    /// Get a page of claims received by a user. Claims follow a rotated
+    /// profile, so its earlier addresses can be passed too. They stay readable
+    /// after the profile is deactivated or deleted, so they remain attributable.
+    /// `cursor` is the position to start from (0 for the first page) and
+    /// `limit` is capped at `MAX_PAGE_SIZE`.
+    pub fn get_user_claims_page(e: Env, account: Address, cursor: u32, limit: u32) -> ClaimPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let account = resolve_address(&e, &account);
+        let total = get_user_claim_count(&e, &account);
+        let claim_ids = get_user_claims_range(&e, &account, cursor, limit);
+        load_claims_page(&e, claim_ids, cursor, total)
+    }
+
+    /// Get a page of claims issued by a user. Claims follow a rotated
+    /// profile, so its earlier addresses can be passed too. They stay readable
+    /// after the profile is deactivated or deleted, so they remain attributable.
+    /// `cursor` is the position to start from (0 for the first page) and
+    /// `limit` is capped at `MAX_PAGE_SIZE`.
+    pub fn get_issuer_claims_page(e: Env, account: Address, cursor: u32, limit: u32) -> ClaimPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let account = resolve_address(&e, &account);
+        let total = get_issuer_claim_count(&e, &account);
+        let claim_ids = get_issuer_claims_range(&e, &account, cursor, limit);
+        load_claims_page(&e, claim_ids, cursor, total)
     }
 
     /// Get total number of claims
@@ -164,57 +1136,193 @@ impl OfferHub {
         get_next_claim_id(&e)
     }
 
-    /// Get reputation score
+    /// Get reputation score, computed with the active scoring table
     pub fn get_reputation_score(e: Env, account: Address) -> u32 {
-        let profile = match get_profile(&e, &account) {
-            Some(p) => p,
-            None => return 0,
-        };
+        reputation_score(&e, &account)
+    }
 
-        let claims = get_user_claims(&e, &account);
-        let mut score: u32 = 0;
-
-        // 1. Claims score
-        for id in claims.iter() {
-            if let Some(claim) = get_claim(&e, id) {
-                // Only approved claims count (all are approved now, but good check)
-                if claim.status == ClaimStatus::Approved {
-                    let tipo = claim.claim_type;
-                    // Check type string content
-                    // Note: In Soroban String comparison can be tricky if not careful with allocation
-                    // We'll do basic starts_with logic manually or exact match
-                    
-                    // Simple exact match for "job_completed"
-                    if tipo == String::from_str(&e, "job_completed") {
-                        score += 10;
-                    } else {
-                        // Check if starts with "skill_"
-                        // For MVP simpler: just check exact types or default
-                        // Implementing starts_with manually is verbose, let's assume specific types for now
-                        // or just give 5 points for everything else as a baseline + bonus
-                        score += 5;
-                    }
-                }
-            }
-        }
+    /// Get the issuer-weighted reputation of an account, where each claim
+    /// counts in proportion to its issuer's own reputation. Returns the score
+    /// stored for the current epoch by `refresh_weighted_reputation`, or `None`
+    /// if the account was not refreshed during this epoch.
+    pub fn get_weighted_reputation(e: Env, account: Address) -> Option<u32> {
+        get_weighted_reputation(&e, &account)
+            .filter(|stored| stored.epoch == current_epoch(&e))
+            .map(|stored| stored.score)
+    }
 
-        // 2. Age score (weeks since joined)
-        // 604800 seconds in a week
-        let current_time = e.ledger().timestamp();
-        if current_time > profile.joined_at {
-            let weeks = (current_time - profile.joined_at) / 604800;
-            // Cap age score to avoid overflow if very old (unlikely)
-            if weeks > 0 {
-                 score += weeks as u32;
-            }
-        }
+    /// Get how the reputation score of an account is made up.
+    /// `total` always equals `get_reputation_score`.
+    pub fn get_reputation_breakdown(e: Env, account: Address) -> ReputationBreakdown {
+        reputation_breakdown(&e, &account)
+    }
 
-        score
+    /// Get the scoring table used by `get_reputation_score`
+    pub fn get_scoring_config(e: Env) -> ScoringConfig {
+        active_scoring_config(&e)
     }
     
     /// Get DID for an address
     pub fn get_did(e: Env, account: Address) -> Option<String> {
-        get_profile(&e, &account).and_then(|p| p.did)
+        Self::get_profile(e, account).and_then(|p| p.did)
+    }
+
+    /// Get the address whose profile a DID is linked to
+    pub fn get_address_by_did(e: Env, did: String) -> Option<Address> {
+        get_did_owner(&e, &did).filter(|owner| is_active_profile(&e, owner))
+    }
+
+    /// Get the address of the profile holding a handle
+    pub fn get_address_by_handle(e: Env, handle: Symbol) -> Option<Address> {
+        get_handle_record(&e, &handle)
+            .filter(|record| record.released_at.is_none() && is_active_profile(&e, &record.owner))
+            .map(|record| record.owner)
+    }
+
+    /// Get the DID methods accepted by `link_did`
+    pub fn get_did_methods(e: Env) -> Vec<String> {
+        active_did_methods(&e)
+    }
+
+    /// Get the URI schemes accepted for profile metadata
+    pub fn get_uri_schemes(e: Env) -> Vec<String> {
+        active_uri_schemes(&e)
+    }
+
+    /// Whether a public key is a registered email verifier key
+    pub fn is_email_verifier_key(e: Env, public_key: BytesN<32>) -> bool {
+        is_email_verifier_key(&e, &public_key)
+    }
+
+    /// Get the nonce the next email attestation of an owner must carry
+    pub fn get_email_nonce(e: Env, owner: Address) -> u64 {
+        get_email_nonce(&e, &owner)
+    }
+
+    /// Whether an address is an approved linked account verifier
+    pub fn is_verifier(e: Env, account: Address) -> bool {
+        is_verifier(&e, &account)
+    }
+
+    /// Get the platforms accepted in linked accounts
+    pub fn get_platforms(e: Env) -> Vec<Symbol> {
+        active_platforms(&e)
+    }
+}
+
+/// Move a profile, its claim indexes and its DID from `old_owner` to
+/// `new_owner`, leaving a rotation record at the old address
+fn move_identity(e: &Env, old_owner: Address, mut profile: Profile, new_owner: Address) -> Result<(), Error> {
+    if has_profile(e, &new_owner) {
+        return Err(Error::ProfileAlreadyExists);
+    }
+    if get_rotated_to(e, &new_owner).is_some() {
+        return Err(Error::AddressRotated);
+    }
+    // Their claims would stay indexed under the new address itself, out of
+    // reach of the duplicate check, which keys receivers by registration
+    if get_user_claim_count(e, &new_owner) > 0 || get_issuer_claim_count(e, &new_owner) > 0 {
+        return Err(Error::AddressHasClaims);
+    }
+
+    let previous = profile.clone();
+    profile.owner = new_owner.clone();
+    save_profile_version(e, &new_owner, &previous, profile.clone());
+    remove_profile(e, &old_owner);
+    remove_recovery(e, &old_owner);
+
+    move_user_claims(e, &old_owner, &new_owner);
+    move_issuer_claims(e, &old_owner, &new_owner);
+
+    if let Some(did) = profile.did.as_ref() {
+        set_did_owner(e, did, &new_owner);
+    }
+    if let Some(handle) = profile.handle.as_ref() {
+        set_handle_record(e, handle, &HandleRecord {
+            owner: new_owner.clone(),
+            released_at: None,
+        });
+    }
+    set_rotated_to(e, &old_owner, &new_owner);
+
+    // Emit event
+    AddressRotatedEvent { old_owner, new_owner }.publish(e);
+
+    Ok(())
+}
+
+/// Store a changed profile as its next version, recording the change in its history
+fn save_profile(e: &Env, owner: &Address, profile: Profile) {
+    let previous = get_profile(e, owner).expect("profile not found");
+    save_profile_version(e, owner, &previous, profile);
+}
+
+fn save_profile_version(e: &Env, owner: &Address, previous: &Profile, mut profile: Profile) {
+    profile.version = previous.version + 1;
+    profile.updated_at = e.ledger().timestamp();
+
+    let previous_hash = e.crypto().sha256(&previous.clone().to_xdr(e)).into();
+    set_profile_history_entry(e, &profile.registered_as, &ProfileHistoryEntry {
+        version: profile.version,
+        updated_at: profile.updated_at,
+        previous_hash,
+    });
+    set_profile(e, owner, &profile);
+}
+
+/// Take the linked accounts sent by the owner, keeping the verification of
+/// entries left unchanged and clearing it on every other entry
+fn keep_verifications(e: &Env, accounts: Vec<LinkedAccount>, previous: &Vec<LinkedAccount>) -> Vec<LinkedAccount> {
+    let mut result = Vec::new(e);
+    for mut account in accounts.iter() {
+        let unchanged = previous
+            .iter()
+            .find(|old| old.platform == account.platform && old.handle == account.handle);
+        match unchanged {
+            Some(old) => {
+                account.verified_by = old.verified_by;
+                account.verified_at = old.verified_at;
+                account.proof_hash = old.proof_hash;
+            }
+            None => {
+                account.verified_by = None;
+                account.verified_at = None;
+                account.proof_hash = None;
+            }
+        }
+        result.push_back(account);
     }
+    result
 }
 
+/// Mark a handle as released, starting its cooldown
+fn release_handle(e: &Env, owner: &Address, handle: &Symbol) {
+    set_handle_record(e, handle, &HandleRecord {
+        owner: owner.clone(),
+        released_at: Some(e.ledger().timestamp()),
+    });
+}
+
+/// Read a claim for a getter, with its derived status
+fn load_claim(e: &Env, claim_id: u64) -> Option<Claim> {
+    get_claim(e, claim_id).map(|mut claim| {
+        claim.status = derived_status(e, &claim);
+        claim
+    })
+}
+
+/// Load the claims for a slice of an index and compute the next cursor
+fn load_claims_page(e: &Env, claim_ids: Vec<u64>, cursor: u32, total: u32) -> ClaimPage {
+    let mut claims = Vec::new(e);
+    for id in claim_ids.iter() {
+        if let Some(claim) = load_claim(e, id) {
+            claims.push_back(claim);
+        }
+    }
+
+    let next = cursor.saturating_add(claim_ids.len());
+    ClaimPage {
+        claims,
+        next_cursor: if next < total { Some(next) } else { None },
+    }
+}
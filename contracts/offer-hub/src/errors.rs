@@ -11,9 +11,97 @@ pub enum Error {
     ProfileNotFound = 2,
     /// Claim not found
     ClaimNotFound = 3,
+    /// Caller is not allowed to perform this action
+    Unauthorized = 4,
+    /// Claim has already been approved or rejected
+    ClaimAlreadyProcessed = 5,
     /// Invalid DID format
     InvalidDid = 6,
     /// Invalid metadata URI
     InvalidMetadataUri = 7,
+    /// Caller is not the issuer of the claim
+    NotClaimIssuer = 8,
+    /// Claim has already been revoked
+    ClaimAlreadyRevoked = 9,
+    /// No admin transfer has been proposed
+    NoPendingAdmin = 10,
+    /// Stored schema version does not match the version being migrated from
+    SchemaVersionMismatch = 11,
+    /// Contract (or this group of entry points) is paused
+    ContractPaused = 12,
+    /// Scoring table is malformed
+    InvalidScoringConfig = 13,
+    /// Issuer and receiver of a claim are the same address
+    SelfClaimNotAllowed = 14,
+    /// DID method is not in the allowlist
+    DidMethodNotAllowed = 15,
+    /// DID is already linked to another profile
+    DidAlreadyLinked = 16,
+    /// Profile has no linked DID
+    DidNotLinked = 17,
+    /// Address has been rotated to a new address and can no longer hold a profile
+    AddressRotated = 18,
+    /// Guardian list or recovery threshold is malformed
+    InvalidGuardians = 19,
+    /// Caller is not a guardian of the profile
+    NotGuardian = 20,
+    /// Profile has no pending recovery
+    RecoveryNotFound = 21,
+    /// No recovery to this address is pending, only to others
+    RecoveryTargetMismatch = 22,
+    /// Recovery lacks guardian approvals or its delay has not passed
+    RecoveryNotReady = 23,
+    /// Handle has an invalid length or characters
+    InvalidHandle = 24,
+    /// Handle is reserved and can't be claimed
+    HandleReserved = 25,
+    /// Handle belongs to another profile
+    HandleTaken = 26,
+    /// Handle was released recently and only its previous owner can claim it
+    HandleCoolingDown = 27,
+    /// Profile has no handle
+    HandleNotSet = 28,
+    /// Display name is empty or too long
+    InvalidDisplayName = 29,
+    /// Country code is not an ISO 3166-1 alpha-2 code
+    InvalidCountryCode = 30,
+    /// Profile has more linked accounts than allowed
+    TooManyLinkedAccounts = 31,
+    /// Linked account platform is not in the allowlist
+    PlatformNotAllowed = 32,
+    /// Profile links two accounts on the same platform
+    DuplicatePlatform = 33,
+    /// Metadata URI scheme is not in the allowlist
+    UriSchemeNotAllowed = 34,
+    /// `ipfs://` metadata URI does not start with a valid CID
+    InvalidIpfsCid = 35,
+    /// Profile is deactivated
+    ProfileDeactivated = 36,
+    /// Profile has been deleted
+    ProfileDeleted = 37,
+    /// Profile is not deactivated
+    ProfileNotDeactivated = 38,
+    /// Caller is not an approved linked account verifier
+    NotVerifier = 39,
+    /// Profile has no linked account with this platform and handle
+    LinkedAccountNotFound = 40,
+    /// Public key is not a registered email verifier key
+    UnknownVerifierKey = 41,
+    /// Attestation expiry has passed
+    AttestationExpired = 42,
+    /// Attestation nonce is not the owner's next nonce
+    InvalidNonce = 43,
+    /// Claim would have more evidence entries than allowed
+    TooMuchEvidence = 44,
+    /// Receiver already has a pending or approved claim with this proof hash
+    DuplicateProofHash = 45,
+    /// Claim validity window is empty or already over
+    InvalidValidityWindow = 46,
+    /// Linked account handle is empty or too long
+    InvalidLinkedHandle = 47,
+    /// Claims can't change until `migrate` has finished
+    MigrationPending = 48,
+    /// Profile can't be moved to an address that already sent or received claims
+    AddressHasClaims = 49,
 }
 
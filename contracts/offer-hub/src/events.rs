@@ -1,38 +1,324 @@
-use soroban_sdk::{contracttype, Address, String};
+use soroban_sdk::{contractevent, Address, BytesN, String, Symbol, Vec};
+use crate::types::{ClaimEvidence, PauseScope, RevocationReason, ScoringConfig};
+
+// Every event is published under two fixed topics, the event name and the
+// event schema version (`"v1"`), followed by the fields marked `#[topic]`.
+// Bump the version topic when the fields of an event change, so indexers
+// can decode each version from the contract spec.
+
+// ==========================================================================
+// Profile events
+// ==========================================================================
 
 /// Event emitted when a new profile is registered
-#[contracttype]
+#[contractevent(topics = ["profile_registered", "v1"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ProfileRegisteredEvent {
+    #[topic]
+    pub owner: Address,
+    pub metadata_uri: String,
+}
+
+/// Event emitted when a profile is updated
+#[contractevent(topics = ["profile_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileUpdatedEvent {
+    #[topic]
     pub owner: Address,
     pub metadata_uri: String,
 }
 
+/// Event emitted when the owner deactivates a profile
+#[contractevent(topics = ["profile_deactivated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileDeactivatedEvent {
+    #[topic]
+    pub owner: Address,
+}
+
+/// Event emitted when the owner reactivates a profile
+#[contractevent(topics = ["profile_reactivated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileReactivatedEvent {
+    #[topic]
+    pub owner: Address,
+}
+
+/// Event emitted when the owner deletes a profile
+#[contractevent(topics = ["profile_deleted", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileDeletedEvent {
+    #[topic]
+    pub owner: Address,
+}
+
+/// Event emitted when a verifier attests a linked account
+#[contractevent(topics = ["linked_account_verified", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinkedAccountVerifiedEvent {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub verifier: Address,
+    pub platform: Symbol,
+    pub handle: String,
+    pub proof_hash: BytesN<32>,
+}
+
+/// Event emitted when an email verifier's attestation of a profile's email hash is accepted
+#[contractevent(topics = ["email_verified", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmailVerifiedEvent {
+    #[topic]
+    pub owner: Address,
+    pub email_hash: BytesN<32>,
+    pub verifier_key: BytesN<32>,
+}
+
+/// Event emitted when a DID is linked to a profile
+#[contractevent(topics = ["did_linked", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DidLinkedEvent {
+    #[topic]
+    pub owner: Address,
+    pub did: String,
+}
+
+/// Event emitted when a DID is unlinked from a profile
+#[contractevent(topics = ["did_unlinked", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DidUnlinkedEvent {
+    #[topic]
+    pub owner: Address,
+    pub did: String,
+}
+
+/// Event emitted when a profile claims a handle
+#[contractevent(topics = ["handle_claimed", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HandleClaimedEvent {
+    #[topic]
+    pub owner: Address,
+    pub handle: Symbol,
+    /// Handle released by the rename, if any
+    pub previous_handle: Option<Symbol>,
+}
+
+/// Event emitted when a profile releases its handle
+#[contractevent(topics = ["handle_released", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HandleReleasedEvent {
+    #[topic]
+    pub owner: Address,
+    pub handle: Symbol,
+}
+
+/// Event emitted when a profile is rotated to a new address
+#[contractevent(topics = ["address_rotated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddressRotatedEvent {
+    #[topic]
+    pub old_owner: Address,
+    #[topic]
+    pub new_owner: Address,
+}
+
+/// Event emitted when a profile owner sets their recovery guardians
+#[contractevent(topics = ["guardians_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardiansUpdatedEvent {
+    #[topic]
+    pub owner: Address,
+    pub guardians: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Event emitted when a guardian approves the recovery of a profile
+#[contractevent(topics = ["recovery_approved", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryApprovedEvent {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub guardian: Address,
+    pub new_owner: Address,
+    /// Set once enough guardians approved
+    pub executable_at: Option<u64>,
+}
+
+/// Event emitted when the owner cancels a pending recovery
+#[contractevent(topics = ["recovery_cancelled", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryCancelledEvent {
+    #[topic]
+    pub owner: Address,
+    pub new_owner: Address,
+}
+
+// ==========================================================================
+// Claim events
+// ==========================================================================
+
 /// Event emitted when a claim is added
-#[contracttype]
+#[contractevent(topics = ["claim_added", "v1"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClaimAddedEvent {
     pub claim_id: u64,
+    #[topic]
     pub issuer: Address,
+    #[topic]
     pub receiver: Address,
     pub claim_type: String,
 }
 
 /// Event emitted when a claim is approved
-#[contracttype]
+#[contractevent(topics = ["claim_approved", "v1"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClaimApprovedEvent {
     pub claim_id: u64,
+    #[topic]
     pub issuer: Address,
+    #[topic]
     pub receiver: Address,
 }
 
 /// Event emitted when a claim is rejected
-#[contracttype]
+#[contractevent(topics = ["claim_rejected", "v1"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClaimRejectedEvent {
     pub claim_id: u64,
+    #[topic]
+    pub issuer: Address,
+    #[topic]
+    pub receiver: Address,
+}
+
+/// Event emitted when a claim is revoked by its issuer
+#[contractevent(topics = ["claim_revoked", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimRevokedEvent {
+    pub claim_id: u64,
+    #[topic]
     pub issuer: Address,
+    #[topic]
     pub receiver: Address,
+    pub reason: RevocationReason,
+}
+
+/// Event emitted when the issuer or the receiver attaches evidence to a claim
+#[contractevent(topics = ["claim_evidence_added", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimEvidenceAddedEvent {
+    pub claim_id: u64,
+    #[topic]
+    pub added_by: Address,
+    pub evidence: Vec<ClaimEvidence>,
+}
+
+/// Event emitted when the issuer-weighted reputation of an account is stored for an epoch
+#[contractevent(topics = ["weighted_reputation_refreshed", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WeightedReputationRefreshedEvent {
+    #[topic]
+    pub account: Address,
+    pub score: u32,
+    pub epoch: u64,
+}
+
+// ==========================================================================
+// Admin events
+// ==========================================================================
+
+/// Event emitted when the admin proposes a new admin
+#[contractevent(topics = ["admin_proposed", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposedEvent {
+    #[topic]
+    pub admin: Address,
+    #[topic]
+    pub pending_admin: Address,
+}
+
+/// Event emitted when a proposed admin accepts the role
+#[contractevent(topics = ["admin_changed", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminChangedEvent {
+    #[topic]
+    pub previous_admin: Address,
+    #[topic]
+    pub new_admin: Address,
+}
+
+/// Event emitted when the contract code is upgraded
+#[contractevent(topics = ["contract_upgraded", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUpgradedEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+/// Event emitted when a storage migration completes
+#[contractevent(topics = ["migration_completed", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationCompletedEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// Event emitted when the admin pauses a group of entry points
+#[contractevent(topics = ["contract_paused", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractPausedEvent {
+    pub scope: PauseScope,
 }
 
+/// Event emitted when the admin unpauses a group of entry points
+#[contractevent(topics = ["contract_unpaused", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUnpausedEvent {
+    pub scope: PauseScope,
+}
+
+/// Event emitted when the admin replaces the scoring table
+#[contractevent(topics = ["scoring_config_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoringConfigUpdatedEvent {
+    pub config: ScoringConfig,
+}
+
+/// Event emitted when the admin replaces the DID method allowlist
+#[contractevent(topics = ["did_methods_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DidMethodsUpdatedEvent {
+    pub methods: Vec<String>,
+}
+
+/// Event emitted when the admin replaces the linked account platform allowlist
+#[contractevent(topics = ["platforms_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlatformsUpdatedEvent {
+    pub platforms: Vec<Symbol>,
+}
+
+/// Event emitted when the admin replaces the metadata URI scheme allowlist
+#[contractevent(topics = ["uri_schemes_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UriSchemesUpdatedEvent {
+    pub schemes: Vec<String>,
+}
+
+/// Event emitted when the admin approves or removes a linked account verifier
+#[contractevent(topics = ["verifier_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifierUpdatedEvent {
+    #[topic]
+    pub verifier: Address,
+    pub approved: bool,
+}
+
+/// Event emitted when the admin registers or removes an email verifier key
+#[contractevent(topics = ["email_verifier_key_updated", "v1"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmailVerifierKeyUpdatedEvent {
+    pub public_key: BytesN<32>,
+    pub approved: bool,
+}
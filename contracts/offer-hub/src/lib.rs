@@ -8,6 +8,8 @@ mod types;
 mod storage;
 mod auth;
 mod contract;
+mod migration;
+mod scoring;
 
 #[cfg(test)]
 mod test;
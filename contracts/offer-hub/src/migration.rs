@@ -0,0 +1,244 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, Map, String, Symbol, TryFromVal, Val, Vec};
+use crate::types::{Claim, ClaimStatus, LinkedAccount, Profile, ProfileStatus, ScoringConfig};
+use crate::storage::{
+    add_issuer_claim, add_proof_hash_claim, add_user_claim, get_migration_cursor,
+    get_next_claim_id, get_raw_claim, registered_address,
+    remove_legacy_claim_indexes, remove_migration_cursor, resolve_address, set_claim,
+    set_migration_cursor, set_receiver_proof_hash_claim,
+};
+
+/// Schema version written by this version of the contract
+pub const CURRENT_SCHEMA_VERSION: u32 = 3;
+
+/// Maximum number of claims rewritten by a single `migrate` call
+pub const MIGRATION_BATCH_SIZE: u64 = 25;
+
+// ==========================================================================
+// Schema version 1
+//
+// Layouts written before claim revocation and bucketed claim indexes.
+// ==========================================================================
+
+/// Claim layout used by schema version 1
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimV1 {
+    pub id: u64,
+    pub issuer: Address,
+    pub receiver: Address,
+    pub claim_type: String,
+    pub proof_hash: BytesN<32>,
+    pub status: ClaimStatus,
+}
+
+/// Linked account layout used before linked account verification
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinkedAccountV1 {
+    pub platform: Symbol,
+    pub handle: String,
+}
+
+/// Profile layout used before recovery guardians were added. Fields added to
+/// `Profile` since then are read with their defaults, see `decode_profile`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileV1 {
+    pub owner: Address,
+    pub metadata_uri: String,
+    pub did: Option<String>,
+    pub display_name: String,
+    pub country_code: Option<Symbol>,
+    pub email_hash: Option<BytesN<32>>,
+    pub linked_accounts: Vec<LinkedAccountV1>,
+    pub joined_at: u64,
+}
+
+/// Storage keys used by schema version 1 for the unbucketed claim indexes.
+/// Encodes the same as the old `DataKey::UserClaims(Address)` / `DataKey::IssuerClaims(Address)`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LegacyDataKey {
+    UserClaims(Address),
+    IssuerClaims(Address),
+}
+
+// ==========================================================================
+// Decoding
+//
+// Records are stored as maps keyed by field name, so the layout of a stored
+// record can be told apart by the fields it carries. Decoding a map into a
+// struct with a different set of fields traps, so the layout must be
+// checked before decoding, or the record read field by field.
+// ==========================================================================
+
+/// Read one field of a stored record, `None` if the record predates the field
+fn field<T: TryFromVal<Env, Val>>(e: &Env, raw: &Map<Symbol, Val>, name: &str) -> Option<T> {
+    raw.get(Symbol::new(e, name))
+        .map(|val| T::try_from_val(e, &val).unwrap_or_else(|_| panic!("unknown record layout")))
+}
+
+/// Decode a stored linked account, filling in the verification fields for
+/// accounts written before linked account verification
+fn decode_linked_account(e: &Env, raw: Map<Symbol, Val>) -> LinkedAccount {
+    LinkedAccount {
+        platform: field(e, &raw, "platform").expect("unknown profile layout"),
+        handle: field(e, &raw, "handle").expect("unknown profile layout"),
+        verified_by: field(e, &raw, "verified_by").unwrap_or(None),
+        verified_at: field(e, &raw, "verified_at").unwrap_or(None),
+        proof_hash: field(e, &raw, "proof_hash").unwrap_or(None),
+    }
+}
+
+/// Decode a stored profile, filling in fields added after it was written.
+/// Profiles can't be enumerated, so old ones are upgraded when next written.
+pub fn decode_profile(e: &Env, raw: Map<Symbol, Val>) -> Profile {
+    let joined_at: u64 = field(e, &raw, "joined_at").expect("unknown profile layout");
+    let owner: Address = field(e, &raw, "owner").expect("unknown profile layout");
+    Profile {
+        owner: owner.clone(),
+        metadata_uri: field(e, &raw, "metadata_uri").expect("unknown profile layout"),
+        did: field(e, &raw, "did").unwrap_or(None),
+        display_name: field(e, &raw, "display_name").expect("unknown profile layout"),
+        country_code: field(e, &raw, "country_code").unwrap_or(None),
+        email_hash: field(e, &raw, "email_hash").unwrap_or(None),
+        linked_accounts: {
+            let raw_accounts: Vec<Map<Symbol, Val>> =
+                field(e, &raw, "linked_accounts").unwrap_or_else(|| Vec::new(e));
+            let mut accounts = Vec::new(e);
+            for account in raw_accounts.iter() {
+                accounts.push_back(decode_linked_account(e, account));
+            }
+            accounts
+        },
+        joined_at,
+        // Added with guardian recovery
+        guardians: field(e, &raw, "guardians").unwrap_or_else(|| Vec::new(e)),
+        recovery_threshold: field(e, &raw, "recovery_threshold").unwrap_or(0),
+        // Added with handles
+        handle: field(e, &raw, "handle").unwrap_or(None),
+        // Added with metadata hash pinning
+        metadata_hash: field(e, &raw, "metadata_hash").unwrap_or(None),
+        // Added with profile deactivation and deletion
+        status: field(e, &raw, "status").unwrap_or(ProfileStatus::Active),
+        // Added with email verification
+        email_verified: field(e, &raw, "email_verified").unwrap_or(false),
+        // Added with profile history
+        version: field(e, &raw, "version").unwrap_or(1),
+        updated_at: field(e, &raw, "updated_at").unwrap_or(joined_at),
+        registered_as: field(e, &raw, "registered_as").unwrap_or(owner),
+    }
+}
+
+/// Decode a stored claim, filling in fields added after it was written
+pub fn decode_claim(e: &Env, raw: Map<Symbol, Val>) -> Claim {
+    Claim {
+        id: field(e, &raw, "id").expect("unknown claim layout"),
+        issuer: field(e, &raw, "issuer").expect("unknown claim layout"),
+        receiver: field(e, &raw, "receiver").expect("unknown claim layout"),
+        claim_type: field(e, &raw, "claim_type").expect("unknown claim layout"),
+        proof_hash: field(e, &raw, "proof_hash").expect("unknown claim layout"),
+        status: field(e, &raw, "status").expect("unknown claim layout"),
+        // Added with claim revocation (schema version 2)
+        revoked_at: field(e, &raw, "revoked_at").unwrap_or(None),
+        // Added with claim evidence
+        metadata_uri: field(e, &raw, "metadata_uri").unwrap_or(None),
+        evidence: field(e, &raw, "evidence").unwrap_or_else(|| Vec::new(e)),
+        // Added with claim validity windows
+        valid_from: field(e, &raw, "valid_from").unwrap_or(None),
+        valid_until: field(e, &raw, "valid_until").unwrap_or(None),
+        // Added with time-decayed scoring
+        issued_at: field(e, &raw, "issued_at").unwrap_or(None),
+    }
+}
+
+/// Decode the stored scoring table, filling in settings added after it was set
+pub fn decode_scoring_config(e: &Env, raw: Map<Symbol, Val>) -> ScoringConfig {
+    ScoringConfig {
+        rules: field(e, &raw, "rules").expect("unknown scoring config layout"),
+        default_points: field(e, &raw, "default_points").expect("unknown scoring config layout"),
+        default_max_points: field(e, &raw, "default_max_points").expect("unknown scoring config layout"),
+        weekly_age_bonus: field(e, &raw, "weekly_age_bonus").expect("unknown scoring config layout"),
+        ignore_self_claims: field(e, &raw, "ignore_self_claims").expect("unknown scoring config layout"),
+        require_issuer_profile: field(e, &raw, "require_issuer_profile").expect("unknown scoring config layout"),
+        repeat_issuer_factor_bps: field(e, &raw, "repeat_issuer_factor_bps").expect("unknown scoring config layout"),
+        // Added with claim validity windows
+        expiry_decay_period: field(e, &raw, "expiry_decay_period").unwrap_or(0),
+        // Added with time-decayed scoring
+        claim_half_life: field(e, &raw, "claim_half_life").unwrap_or(0),
+        max_age_points: field(e, &raw, "max_age_points").unwrap_or(None),
+    }
+}
+
+// ==========================================================================
+// Migrations
+// ==========================================================================
+
+/// Rewrite the next batch of schema version 1 claims into the current layout
+/// and rebuild the bucketed claim and proof hash indexes from them.
+/// Returns `true` once every claim has been migrated.
+pub fn migrate_v1_claims(e: &Env) -> bool {
+    let start = get_migration_cursor(e);
+    let total = get_next_claim_id(e);
+    let end = start.saturating_add(MIGRATION_BATCH_SIZE).min(total);
+
+    for id in start..end {
+        // Claims can't be added until the migration is done, so every
+        // claim below the next ID was written before the upgrade
+        let raw = match get_raw_claim(e, id) {
+            Some(raw) => raw,
+            None => continue,
+        };
+
+        let claim = decode_claim(e, raw);
+        set_claim(e, id, &claim);
+        // Index under the current address of accounts rotated before the migration
+        add_user_claim(e, &resolve_address(e, &claim.receiver), id);
+        add_issuer_claim(e, &resolve_address(e, &claim.issuer), id);
+        remove_legacy_claim_indexes(e, &claim.receiver, &claim.issuer);
+        index_proof_hash(e, &claim);
+    }
+
+    if end < total {
+        set_migration_cursor(e, end);
+        return false;
+    }
+
+    remove_migration_cursor(e);
+    true
+}
+
+/// Index the next batch of schema version 2 claims by proof hash.
+/// Returns `true` once every claim has been indexed.
+pub fn migrate_v2_proof_hashes(e: &Env) -> bool {
+    let start = get_migration_cursor(e);
+    let total = get_next_claim_id(e);
+    let end = start.saturating_add(MIGRATION_BATCH_SIZE).min(total);
+
+    for id in start..end {
+        // Claims can't be added until the migration is done, so every
+        // claim below the next ID was written before the upgrade
+        let raw = match get_raw_claim(e, id) {
+            Some(raw) => raw,
+            None => continue,
+        };
+        index_proof_hash(e, &decode_claim(e, raw));
+    }
+
+    if end < total {
+        set_migration_cursor(e, end);
+        return false;
+    }
+
+    remove_migration_cursor(e);
+    true
+}
+
+/// Add a migrated claim to the proof hash indexes. Claims are migrated in ID
+/// order, so the latest live claim of each receiver ends up as its duplicate key.
+fn index_proof_hash(e: &Env, claim: &Claim) {
+    add_proof_hash_claim(e, &claim.proof_hash, claim.id);
+    if matches!(claim.status, ClaimStatus::Pending | ClaimStatus::Approved) {
+        set_receiver_proof_hash_claim(e, &claim.proof_hash, &registered_address(e, &claim.receiver), claim.id);
+    }
+}
@@ -0,0 +1,365 @@
+use soroban_sdk::{Address, Env, Map, String, Vec};
+use crate::errors::Error;
+use crate::types::{
+    Claim, ClaimStatus, ClaimTypePoints, ProfileStatus, ReputationBreakdown, ScoringConfig, ScoringRule,
+};
+use crate::storage::{
+    get_claim, get_profile, get_scoring_config, get_user_claim_count, get_user_claims_range,
+    get_weighted_reputation,
+};
+use crate::auth::is_active_profile;
+
+/// Maximum number of rules in the scoring table
+pub const MAX_SCORING_RULES: u32 = 20;
+
+const SECONDS_PER_WEEK: u64 = 604800;
+
+/// Basis points in 100%
+const BPS: u32 = 10_000;
+
+/// Most received claims read when computing a score. Only the latest ones
+/// count, so the cost of scoring an account stays bounded.
+pub const MAX_SCORED_CLAIMS: u32 = 100;
+
+/// Length of the epochs weighted reputation scores are stored for
+pub const WEIGHTED_REPUTATION_EPOCH: u64 = 24 * 60 * 60;
+
+/// Most issuers whose reputation is looked up when weighting the claims of
+/// one account. Claims from further issuers count with the base trust.
+pub const MAX_WEIGHTED_ISSUERS: u32 = 10;
+
+/// Share of its points, in basis points, a claim keeps when its issuer has
+/// no reputation, like the damping term of PageRank
+const BASE_ISSUER_TRUST_BPS: u32 = 1_500;
+
+/// Issuer reputation at which claims from that issuer count in full
+const FULL_ISSUER_TRUST_SCORE: u32 = 100;
+
+/// Scoring table used until the admin sets one: 10 points per `job_completed`
+/// claim, 5 points for every other claim type and 1 point per week of age,
+/// up to a year's worth.
+/// Self-claims and claims from issuers without an active profile are ignored.
+pub fn default_scoring_config(e: &Env) -> ScoringConfig {
+    let mut rules = Vec::new(e);
+    rules.push_back(ScoringRule {
+        claim_type: String::from_str(e, "job_completed"),
+        is_prefix: false,
+        points: 10,
+        max_points: None,
+    });
+
+    ScoringConfig {
+        rules,
+        default_points: 5,
+        default_max_points: None,
+        weekly_age_bonus: 1,
+        ignore_self_claims: true,
+        require_issuer_profile: true,
+        repeat_issuer_factor_bps: None,
+        expiry_decay_period: 0,
+        claim_half_life: 0,
+        max_age_points: Some(52),
+    }
+}
+
+/// Get the scoring table currently in effect
+pub fn active_scoring_config(e: &Env) -> ScoringConfig {
+    get_scoring_config(e).unwrap_or_else(|| default_scoring_config(e))
+}
+
+/// Validates a scoring table before it is stored
+pub fn validate_scoring_config(config: &ScoringConfig) -> Result<(), Error> {
+    if config.rules.len() > MAX_SCORING_RULES {
+        return Err(Error::InvalidScoringConfig);
+    }
+    for rule in config.rules.iter() {
+        if rule.claim_type.is_empty() {
+            return Err(Error::InvalidScoringConfig);
+        }
+    }
+    if config.repeat_issuer_factor_bps.is_some_and(|bps| bps > BPS) {
+        return Err(Error::InvalidScoringConfig);
+    }
+    Ok(())
+}
+
+/// Whether a claim is past its `valid_until` time
+pub fn is_expired(e: &Env, claim: &Claim) -> bool {
+    claim.valid_until.is_some_and(|until| e.ledger().timestamp() >= until)
+}
+
+/// Status reported by getters: pending and approved claims past their
+/// `valid_until` time show as `Expired`
+pub fn derived_status(e: &Env, claim: &Claim) -> ClaimStatus {
+    match claim.status {
+        ClaimStatus::Pending | ClaimStatus::Approved if is_expired(e, claim) => ClaimStatus::Expired,
+        _ => claim.status.clone(),
+    }
+}
+
+/// Find the rule for a claim type. An exact match wins over prefixes, and
+/// the longest matching prefix wins over shorter ones.
+fn find_rule(config: &ScoringConfig, claim_type: &String) -> Option<u32> {
+    let claim_bytes = claim_type.to_bytes();
+    let mut best: Option<(u32, u32)> = None;
+
+    for (index, rule) in config.rules.iter().enumerate() {
+        let index = index as u32;
+        if !rule.is_prefix {
+            if rule.claim_type == *claim_type {
+                return Some(index);
+            }
+            continue;
+        }
+
+        let prefix_len = rule.claim_type.len();
+        if prefix_len > claim_bytes.len() || claim_bytes.slice(0..prefix_len) != rule.claim_type.to_bytes() {
+            continue;
+        }
+        if best.is_none_or(|(_, len)| prefix_len > len) {
+            best = Some((index, prefix_len));
+        }
+    }
+
+    best.map(|(index, _)| index)
+}
+
+/// Weight, in basis points, of a claim `age` seconds old: halved every
+/// `half_life` seconds, interpolated linearly within each period
+fn age_weight(age: u64, half_life: u64) -> u32 {
+    if half_life == 0 {
+        return BPS;
+    }
+    let halvings = age / half_life;
+    if halvings >= 32 {
+        return 0;
+    }
+    let weight = (BPS >> halvings) as u128;
+    // Lose up to half of the current weight by the end of the period
+    let into_period = (age % half_life) as u128;
+    (weight - weight * into_period / (2 * half_life as u128)) as u32
+}
+
+fn capped(points: u32, max_points: Option<u32>) -> u32 {
+    match max_points {
+        Some(max) => points.min(max),
+        None => points,
+    }
+}
+
+/// Compute the reputation score of an account with the active scoring table,
+/// along with how each part of it was earned
+pub fn reputation_breakdown(e: &Env, account: &Address) -> ReputationBreakdown {
+    compute_breakdown(e, account, None)
+}
+
+/// Ids of the latest `MAX_SCORED_CLAIMS` claims received by an account
+fn scored_claim_ids(e: &Env, account: &Address) -> Vec<u64> {
+    let count = get_user_claim_count(e, account);
+    get_user_claims_range(e, account, count.saturating_sub(MAX_SCORED_CLAIMS), MAX_SCORED_CLAIMS)
+}
+
+/// Compute a reputation breakdown. With `issuer_trust`, the points of each
+/// claim are scaled by the trust, in basis points, of its issuer.
+fn compute_breakdown(e: &Env, account: &Address, issuer_trust: Option<&Map<Address, u32>>) -> ReputationBreakdown {
+    let mut breakdown = ReputationBreakdown {
+        claim_points: Vec::new(e),
+        age_points: 0,
+        approved_claims: 0,
+        pending_claims: 0,
+        rejected_claims: 0,
+        revoked_claims: 0,
+        expired_claims: 0,
+        ignored_claims: 0,
+        distinct_issuers: 0,
+        total: 0,
+    };
+
+    // Accounts without an active profile have no reputation
+    let profile = match get_profile(e, account) {
+        Some(p) if p.status == ProfileStatus::Active => p,
+        _ => return breakdown,
+    };
+    let config = active_scoring_config(e);
+    let current_time = e.ledger().timestamp();
+
+    // 1. Claims score, accumulated per rule so caps can be applied.
+    // The last slot collects claims matching no rule.
+    let default_slot = config.rules.len();
+    let mut rule_claims: Vec<u32> = Vec::new(e);
+    let mut rule_points: Vec<u32> = Vec::new(e);
+    for _ in 0..=default_slot {
+        rule_claims.push_back(0);
+        rule_points.push_back(0);
+    }
+    // Weight, in basis points, of the next claim from each issuer
+    let mut issuer_weights: Map<Address, u32> = Map::new(e);
+
+    for id in scored_claim_ids(e, account).iter() {
+        let claim = match get_claim(e, id) {
+            Some(claim) => claim,
+            None => continue,
+        };
+
+        // Only approved claims count; pending, rejected and revoked claims are ignored.
+        // `Expired` is only derived by getters and never stored.
+        match claim.status {
+            ClaimStatus::Approved | ClaimStatus::Expired => breakdown.approved_claims += 1,
+            ClaimStatus::Pending => {
+                breakdown.pending_claims += 1;
+                continue;
+            }
+            ClaimStatus::Rejected => {
+                breakdown.rejected_claims += 1;
+                continue;
+            }
+            ClaimStatus::Revoked => {
+                breakdown.revoked_claims += 1;
+                continue;
+            }
+        }
+
+        // Sybil resistance: self-claims and claims from unregistered, deactivated
+        // or deleted issuers don't count
+        if (config.ignore_self_claims && claim.issuer == *account)
+            || (config.require_issuer_profile && !is_active_profile(e, &claim.issuer))
+        {
+            breakdown.ignored_claims += 1;
+            continue;
+        }
+        // Claims don't count before their validity window opens
+        if claim.valid_from.is_some_and(|from| current_time < from) {
+            breakdown.ignored_claims += 1;
+            continue;
+        }
+        // Expired claims lose their points over the decay period
+        let validity_bps = match claim.valid_until {
+            Some(until) if current_time >= until => {
+                breakdown.expired_claims += 1;
+                let elapsed = current_time - until;
+                if elapsed >= config.expiry_decay_period {
+                    continue;
+                }
+                let remaining = config.expiry_decay_period - elapsed;
+                (remaining as u128 * BPS as u128 / config.expiry_decay_period as u128) as u32
+            }
+            _ => BPS,
+        };
+
+        let (slot, points) = match find_rule(&config, &claim.claim_type) {
+            Some(index) => (index, config.rules.get(index).unwrap().points),
+            None => (default_slot, config.default_points),
+        };
+
+        // Diminishing returns for repeated claims from the same issuer
+        let weight = issuer_weights.get(claim.issuer.clone()).unwrap_or(BPS);
+        let points = (points as u64 * weight as u64 / BPS as u64) as u32;
+        let points = (points as u64 * validity_bps as u64 / BPS as u64) as u32;
+        // Older claims are worth less; claims without an issue time keep full points
+        let age = claim.issued_at.map_or(0, |issued_at| current_time.saturating_sub(issued_at));
+        let points = (points as u64 * age_weight(age, config.claim_half_life) as u64 / BPS as u64) as u32;
+        let points = match issuer_trust {
+            Some(trust) => {
+                let trust_bps = trust.get(claim.issuer.clone()).unwrap_or(BASE_ISSUER_TRUST_BPS);
+                (points as u64 * trust_bps as u64 / BPS as u64) as u32
+            }
+            None => points,
+        };
+        let next_weight = match config.repeat_issuer_factor_bps {
+            Some(factor) => (weight as u64 * factor as u64 / BPS as u64) as u32,
+            None => weight,
+        };
+        issuer_weights.set(claim.issuer.clone(), next_weight);
+
+        rule_claims.set(slot, rule_claims.get(slot).unwrap() + 1);
+        rule_points.set(slot, rule_points.get(slot).unwrap().saturating_add(points));
+    }
+    breakdown.distinct_issuers = issuer_weights.len();
+
+    for slot in 0..=default_slot {
+        let claims = rule_claims.get(slot).unwrap();
+        if claims == 0 {
+            continue;
+        }
+        let entry = if slot == default_slot {
+            ClaimTypePoints {
+                claim_type: String::from_str(e, ""),
+                is_prefix: false,
+                claims,
+                points: capped(rule_points.get(slot).unwrap(), config.default_max_points),
+            }
+        } else {
+            let rule = config.rules.get(slot).unwrap();
+            ClaimTypePoints {
+                claim_type: rule.claim_type,
+                is_prefix: rule.is_prefix,
+                claims,
+                points: capped(rule_points.get(slot).unwrap(), rule.max_points),
+            }
+        };
+        breakdown.total = breakdown.total.saturating_add(entry.points);
+        breakdown.claim_points.push_back(entry);
+    }
+
+    // 2. Age score (weeks since joined)
+    if current_time > profile.joined_at {
+        let weeks = (current_time - profile.joined_at) / SECONDS_PER_WEEK;
+        let weeks = u32::try_from(weeks).unwrap_or(u32::MAX);
+        breakdown.age_points = capped(weeks.saturating_mul(config.weekly_age_bonus), config.max_age_points);
+    }
+    breakdown.total = breakdown.total.saturating_add(breakdown.age_points);
+
+    breakdown
+}
+
+/// Compute the reputation score of an account with the active scoring table
+pub fn reputation_score(e: &Env, account: &Address) -> u32 {
+    reputation_breakdown(e, account).total
+}
+
+/// Epoch the ledger is currently in
+pub fn current_epoch(e: &Env) -> u64 {
+    e.ledger().timestamp() / WEIGHTED_REPUTATION_EPOCH
+}
+
+/// Trust, in basis points, given to claims from an issuer with `score` points
+fn issuer_trust_bps(score: u32) -> u32 {
+    let score = score.min(FULL_ISSUER_TRUST_SCORE);
+    BASE_ISSUER_TRUST_BPS + (BPS - BASE_ISSUER_TRUST_BPS) * score / FULL_ISSUER_TRUST_SCORE
+}
+
+/// Weighted score an account had stored for the epoch before the current
+/// one, or 0 if it was not refreshed then
+pub fn previous_weighted_score(e: &Env, account: &Address) -> u32 {
+    let epoch = current_epoch(e);
+    match get_weighted_reputation(e, account) {
+        Some(stored) if stored.epoch == epoch => stored.previous_score,
+        Some(stored) if stored.epoch + 1 == epoch => stored.score,
+        _ => 0,
+    }
+}
+
+/// Compute the issuer-weighted reputation of an account: each claim counts in
+/// proportion to the weighted score its issuer had stored for the previous
+/// epoch, so refreshes within an epoch never change each other's results and
+/// trust spreads one step further through the claim graph every epoch. Only
+/// the first `MAX_WEIGHTED_ISSUERS` issuers are looked up.
+pub fn weighted_reputation(e: &Env, account: &Address) -> u32 {
+    let mut trust: Map<Address, u32> = Map::new(e);
+    for id in scored_claim_ids(e, account).iter() {
+        if trust.len() >= MAX_WEIGHTED_ISSUERS {
+            break;
+        }
+        let claim = match get_claim(e, id) {
+            Some(claim) if claim.status == ClaimStatus::Approved => claim,
+            _ => continue,
+        };
+        if claim.issuer == *account || trust.contains_key(claim.issuer.clone()) {
+            continue;
+        }
+        let issuer_score = previous_weighted_score(e, &claim.issuer);
+        trust.set(claim.issuer, issuer_trust_bps(issuer_score));
+    }
+
+    compute_breakdown(e, account, Some(&trust)).total
+}
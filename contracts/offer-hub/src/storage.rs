@@ -1,5 +1,6 @@
-use soroban_sdk::{Env, Address, Vec};
-use crate::types::{DataKey, Profile, Claim};
+use soroban_sdk::{Env, Address, BytesN, Map, String, Symbol, Val, Vec};
+use crate::types::{DataKey, HandleRecord, PauseScope, Profile, ProfileHistoryEntry, Claim, RecoveryRequest, RevocationReason, ScoringConfig, WeightedReputation};
+use crate::migration::{decode_claim, decode_profile, decode_scoring_config, LegacyDataKey, CURRENT_SCHEMA_VERSION};
 
 #[allow(dead_code)]
 const DAY_IN_LEDGERS: u32 = 17280; // Approximately 1 day
@@ -10,6 +11,9 @@ const CLAIM_LIFETIME: u32 = DAY_IN_LEDGERS * 365; // 1 year
 #[allow(dead_code)]
 const INSTANCE_LIFETIME: u32 = DAY_IN_LEDGERS * 365; // 1 year
 
+/// Number of claim IDs stored per index bucket
+pub const CLAIM_INDEX_BUCKET_SIZE: u32 = 32;
+
 // Profile storage
 pub fn has_profile(e: &Env, owner: &Address) -> bool {
     let key = DataKey::Profile(owner.clone());
@@ -18,7 +22,8 @@ pub fn has_profile(e: &Env, owner: &Address) -> bool {
 
 pub fn get_profile(e: &Env, owner: &Address) -> Option<Profile> {
     let key = DataKey::Profile(owner.clone());
-    e.storage().persistent().get(&key)
+    let raw: Option<Map<Symbol, Val>> = e.storage().persistent().get(&key);
+    raw.map(|raw| decode_profile(e, raw))
 }
 
 pub fn set_profile(e: &Env, owner: &Address, profile: &Profile) {
@@ -27,8 +32,113 @@ pub fn set_profile(e: &Env, owner: &Address, profile: &Profile) {
     e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
 }
 
+pub fn remove_profile(e: &Env, owner: &Address) {
+    let key = DataKey::Profile(owner.clone());
+    e.storage().persistent().remove(&key);
+}
+
+// Profile history
+pub fn get_profile_history_entry(e: &Env, registered_as: &Address, version: u32) -> Option<ProfileHistoryEntry> {
+    let key = DataKey::ProfileHistory(registered_as.clone(), version);
+    e.storage().persistent().get(&key)
+}
+
+pub fn set_profile_history_entry(e: &Env, registered_as: &Address, entry: &ProfileHistoryEntry) {
+    let key = DataKey::ProfileHistory(registered_as.clone(), entry.version);
+    e.storage().persistent().set(&key, entry);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+// DID reverse index
+pub fn get_did_owner(e: &Env, did: &String) -> Option<Address> {
+    let key = DataKey::Did(did.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn set_did_owner(e: &Env, did: &String, owner: &Address) {
+    let key = DataKey::Did(did.clone());
+    e.storage().persistent().set(&key, owner);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+pub fn remove_did_owner(e: &Env, did: &String) {
+    let key = DataKey::Did(did.clone());
+    e.storage().persistent().remove(&key);
+}
+
+// Handle reverse index
+pub fn get_handle_record(e: &Env, handle: &Symbol) -> Option<HandleRecord> {
+    let key = DataKey::Handle(handle.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn set_handle_record(e: &Env, handle: &Symbol, record: &HandleRecord) {
+    let key = DataKey::Handle(handle.clone());
+    e.storage().persistent().set(&key, record);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+// Address rotation
+//
+// When a profile moves to a new address, the old address keeps a record of
+// where it went. Claims are not rewritten, so their issuer and receiver are
+// resolved through these records when read.
+pub fn get_rotated_to(e: &Env, old: &Address) -> Option<Address> {
+    let key = DataKey::RotatedTo(old.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn set_rotated_to(e: &Env, old: &Address, new: &Address) {
+    let key = DataKey::RotatedTo(old.clone());
+    e.storage().persistent().set(&key, new);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+/// Follow rotation records to the address an identity currently lives at
+pub fn resolve_address(e: &Env, account: &Address) -> Address {
+    let mut current = account.clone();
+    while let Some(next) = get_rotated_to(e, &current) {
+        current = next;
+    }
+    current
+}
+
+/// Address the identity behind `account` was registered with, which stays the
+/// same across rotations. Accounts without a profile stand for themselves.
+pub fn registered_address(e: &Env, account: &Address) -> Address {
+    let current = resolve_address(e, account);
+    get_profile(e, &current).map_or(current, |profile| profile.registered_as)
+}
+
+// Guardian recovery (one request per proposed address)
+pub fn get_recovery(e: &Env, owner: &Address) -> Vec<RecoveryRequest> {
+    let key = DataKey::Recovery(owner.clone());
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn set_recovery(e: &Env, owner: &Address, requests: &Vec<RecoveryRequest>) {
+    let key = DataKey::Recovery(owner.clone());
+    e.storage().persistent().set(&key, requests);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+pub fn remove_recovery(e: &Env, owner: &Address) {
+    let key = DataKey::Recovery(owner.clone());
+    e.storage().persistent().remove(&key);
+}
+
 // Claim storage
 pub fn get_claim(e: &Env, id: u64) -> Option<Claim> {
+    get_raw_claim(e, id).map(|raw| {
+        let mut claim = decode_claim(e, raw);
+        claim.issuer = resolve_address(e, &claim.issuer);
+        claim.receiver = resolve_address(e, &claim.receiver);
+        claim
+    })
+}
+
+/// Get a claim as stored, without upgrading it to the current layout
+pub fn get_raw_claim(e: &Env, id: u64) -> Option<Map<Symbol, Val>> {
     let key = DataKey::Claim(id);
     e.storage().persistent().get(&key)
 }
@@ -39,34 +149,376 @@ pub fn set_claim(e: &Env, id: u64, claim: &Claim) {
     e.storage().persistent().extend_ttl(&key, CLAIM_LIFETIME, CLAIM_LIFETIME);
 }
 
-// User claims list (claims received by a user)
-pub fn get_user_claims(e: &Env, user: &Address) -> Vec<u64> {
-    let key = DataKey::UserClaims(user.clone());
-    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+// Proof hash index (claims issued with each proof hash)
+pub fn get_proof_hash_claim_count(e: &Env, proof_hash: &BytesN<32>) -> u32 {
+    index_len(e, &DataKey::ProofHashClaimCount(proof_hash.clone()))
 }
 
-pub fn add_user_claim(e: &Env, user: &Address, claim_id: u64) {
-    let key = DataKey::UserClaims(user.clone());
-    let mut claims = get_user_claims(e, user);
-    claims.push_back(claim_id);
-    e.storage().persistent().set(&key, &claims);
+pub fn get_proof_hash_claims_range(e: &Env, proof_hash: &BytesN<32>, start: u32, limit: u32) -> Vec<u64> {
+    let len = get_proof_hash_claim_count(e, proof_hash);
+    index_range(e, len, |bucket| DataKey::ProofHashClaims(proof_hash.clone(), bucket), start, limit)
+}
+
+pub fn add_proof_hash_claim(e: &Env, proof_hash: &BytesN<32>, claim_id: u64) {
+    let bucket = get_proof_hash_claim_count(e, proof_hash) / CLAIM_INDEX_BUCKET_SIZE;
+    index_push(
+        e,
+        DataKey::ProofHashClaimCount(proof_hash.clone()),
+        DataKey::ProofHashClaims(proof_hash.clone(), bucket),
+        claim_id,
+    );
+}
+
+pub fn get_receiver_proof_hash_claim(e: &Env, proof_hash: &BytesN<32>, receiver: &Address) -> Option<u64> {
+    let key = DataKey::ProofHash(proof_hash.clone(), receiver.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn set_receiver_proof_hash_claim(e: &Env, proof_hash: &BytesN<32>, receiver: &Address, claim_id: u64) {
+    let key = DataKey::ProofHash(proof_hash.clone(), receiver.clone());
+    e.storage().persistent().set(&key, &claim_id);
     e.storage().persistent().extend_ttl(&key, CLAIM_LIFETIME, CLAIM_LIFETIME);
 }
 
-// Issuer claims list (claims issued by a user)
-pub fn get_issuer_claims(e: &Env, issuer: &Address) -> Vec<u64> {
-    let key = DataKey::IssuerClaims(issuer.clone());
-    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+// Revocation reasons (kept apart from the claim, keyed by claim ID)
+pub fn get_revocation_reason(e: &Env, claim_id: u64) -> Option<RevocationReason> {
+    let key = DataKey::RevocationReason(claim_id);
+    e.storage().persistent().get(&key)
 }
 
-pub fn add_issuer_claim(e: &Env, issuer: &Address, claim_id: u64) {
-    let key = DataKey::IssuerClaims(issuer.clone());
-    let mut claims = get_issuer_claims(e, issuer);
-    claims.push_back(claim_id);
-    e.storage().persistent().set(&key, &claims);
+pub fn set_revocation_reason(e: &Env, claim_id: u64, reason: &RevocationReason) {
+    let key = DataKey::RevocationReason(claim_id);
+    e.storage().persistent().set(&key, reason);
     e.storage().persistent().extend_ttl(&key, CLAIM_LIFETIME, CLAIM_LIFETIME);
 }
 
+// Claim indexes
+//
+// The claims received and issued by an account are indexed in fixed-size
+// buckets of claim IDs, plus a counter of how many IDs the index holds.
+// Appending a claim only rewrites the last bucket, and a page read only
+// loads the buckets it overlaps.
+
+fn index_len(e: &Env, count_key: &DataKey) -> u32 {
+    e.storage().persistent().get(count_key).unwrap_or(0)
+}
+
+fn index_push(e: &Env, count_key: DataKey, bucket_key: DataKey, claim_id: u64) {
+    let count = index_len(e, &count_key);
+
+    let mut bucket: Vec<u64> = e.storage().persistent().get(&bucket_key).unwrap_or(Vec::new(e));
+    bucket.push_back(claim_id);
+    e.storage().persistent().set(&bucket_key, &bucket);
+    e.storage().persistent().extend_ttl(&bucket_key, CLAIM_LIFETIME, CLAIM_LIFETIME);
+
+    e.storage().persistent().set(&count_key, &(count + 1));
+    e.storage().persistent().extend_ttl(&count_key, CLAIM_LIFETIME, CLAIM_LIFETIME);
+}
+
+fn index_range(
+    e: &Env,
+    len: u32,
+    bucket_key: impl Fn(u32) -> DataKey,
+    start: u32,
+    limit: u32,
+) -> Vec<u64> {
+    let mut ids = Vec::new(e);
+    let end = start.saturating_add(limit).min(len);
+    let mut pos = start;
+
+    while pos < end {
+        let bucket_index = pos / CLAIM_INDEX_BUCKET_SIZE;
+        let bucket: Vec<u64> = e
+            .storage()
+            .persistent()
+            .get(&bucket_key(bucket_index))
+            .unwrap_or(Vec::new(e));
+
+        let bucket_end = ((bucket_index + 1) * CLAIM_INDEX_BUCKET_SIZE).min(end);
+        while pos < bucket_end {
+            if let Some(id) = bucket.get(pos % CLAIM_INDEX_BUCKET_SIZE) {
+                ids.push_back(id);
+            }
+            pos += 1;
+        }
+    }
+
+    ids
+}
+
+/// Move an index, bucket by bucket, to a key whose index is empty
+fn index_move(
+    e: &Env,
+    from_count_key: DataKey,
+    from_bucket_key: impl Fn(u32) -> DataKey,
+    to_count_key: DataKey,
+    to_bucket_key: impl Fn(u32) -> DataKey,
+) {
+    let len = index_len(e, &from_count_key);
+    if len == 0 {
+        return;
+    }
+
+    for bucket_index in 0..len.div_ceil(CLAIM_INDEX_BUCKET_SIZE) {
+        let from_key = from_bucket_key(bucket_index);
+        let bucket: Vec<u64> = e.storage().persistent().get(&from_key).unwrap_or(Vec::new(e));
+        e.storage().persistent().remove(&from_key);
+
+        let to_key = to_bucket_key(bucket_index);
+        e.storage().persistent().set(&to_key, &bucket);
+        e.storage().persistent().extend_ttl(&to_key, CLAIM_LIFETIME, CLAIM_LIFETIME);
+    }
+
+    e.storage().persistent().set(&to_count_key, &len);
+    e.storage().persistent().extend_ttl(&to_count_key, CLAIM_LIFETIME, CLAIM_LIFETIME);
+    e.storage().persistent().remove(&from_count_key);
+}
+
+// User claims index (claims received by a user)
+pub fn get_user_claim_count(e: &Env, user: &Address) -> u32 {
+    index_len(e, &DataKey::UserClaimCount(user.clone()))
+}
+
+pub fn get_user_claims_range(e: &Env, user: &Address, start: u32, limit: u32) -> Vec<u64> {
+    let len = get_user_claim_count(e, user);
+    index_range(e, len, |bucket| DataKey::UserClaims(user.clone(), bucket), start, limit)
+}
+
+pub fn add_user_claim(e: &Env, user: &Address, claim_id: u64) {
+    let bucket = get_user_claim_count(e, user) / CLAIM_INDEX_BUCKET_SIZE;
+    index_push(
+        e,
+        DataKey::UserClaimCount(user.clone()),
+        DataKey::UserClaims(user.clone(), bucket),
+        claim_id,
+    );
+}
+
+/// Move every claim received by `from` into the index of `to`
+pub fn move_user_claims(e: &Env, from: &Address, to: &Address) {
+    index_move(
+        e,
+        DataKey::UserClaimCount(from.clone()),
+        |bucket| DataKey::UserClaims(from.clone(), bucket),
+        DataKey::UserClaimCount(to.clone()),
+        |bucket| DataKey::UserClaims(to.clone(), bucket),
+    );
+}
+
+// Issuer claims index (claims issued by a user)
+pub fn get_issuer_claim_count(e: &Env, issuer: &Address) -> u32 {
+    index_len(e, &DataKey::IssuerClaimCount(issuer.clone()))
+}
+
+pub fn get_issuer_claims_range(e: &Env, issuer: &Address, start: u32, limit: u32) -> Vec<u64> {
+    let len = get_issuer_claim_count(e, issuer);
+    index_range(e, len, |bucket| DataKey::IssuerClaims(issuer.clone(), bucket), start, limit)
+}
+
+pub fn add_issuer_claim(e: &Env, issuer: &Address, claim_id: u64) {
+    let bucket = get_issuer_claim_count(e, issuer) / CLAIM_INDEX_BUCKET_SIZE;
+    index_push(
+        e,
+        DataKey::IssuerClaimCount(issuer.clone()),
+        DataKey::IssuerClaims(issuer.clone(), bucket),
+        claim_id,
+    );
+}
+
+/// Move every claim issued by `from` into the index of `to`
+pub fn move_issuer_claims(e: &Env, from: &Address, to: &Address) {
+    index_move(
+        e,
+        DataKey::IssuerClaimCount(from.clone()),
+        |bucket| DataKey::IssuerClaims(from.clone(), bucket),
+        DataKey::IssuerClaimCount(to.clone()),
+        |bucket| DataKey::IssuerClaims(to.clone(), bucket),
+    );
+}
+
+/// Remove the schema version 1 (unbucketed) claim indexes of an account pair
+pub fn remove_legacy_claim_indexes(e: &Env, receiver: &Address, issuer: &Address) {
+    e.storage().persistent().remove(&LegacyDataKey::UserClaims(receiver.clone()));
+    e.storage().persistent().remove(&LegacyDataKey::IssuerClaims(issuer.clone()));
+}
+
+// Admin
+pub fn get_admin(e: &Env) -> Address {
+    let key = DataKey::Admin;
+    e.storage().instance().get(&key).expect("admin not set")
+}
+
+pub fn set_admin(e: &Env, admin: &Address) {
+    let key = DataKey::Admin;
+    e.storage().instance().set(&key, admin);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+pub fn get_pending_admin(e: &Env) -> Option<Address> {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().get(&key)
+}
+
+pub fn set_pending_admin(e: &Env, admin: &Address) {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().set(&key, admin);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+pub fn remove_pending_admin(e: &Env) {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().remove(&key);
+}
+
+// Pause flags
+pub fn is_paused(e: &Env, scope: &PauseScope) -> bool {
+    let key = DataKey::Paused(scope.clone());
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn set_paused(e: &Env, scope: &PauseScope, paused: bool) {
+    let key = DataKey::Paused(scope.clone());
+    if paused {
+        e.storage().instance().set(&key, &true);
+        e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+    } else {
+        e.storage().instance().remove(&key);
+    }
+}
+
+// DID method allowlist
+pub fn get_did_methods(e: &Env) -> Option<Vec<String>> {
+    let key = DataKey::DidMethods;
+    e.storage().instance().get(&key)
+}
+
+pub fn set_did_methods(e: &Env, methods: &Vec<String>) {
+    let key = DataKey::DidMethods;
+    e.storage().instance().set(&key, methods);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+// Linked account platform allowlist
+pub fn get_platforms(e: &Env) -> Option<Vec<Symbol>> {
+    let key = DataKey::Platforms;
+    e.storage().instance().get(&key)
+}
+
+pub fn set_platforms(e: &Env, platforms: &Vec<Symbol>) {
+    let key = DataKey::Platforms;
+    e.storage().instance().set(&key, platforms);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+// Metadata URI scheme allowlist
+pub fn get_uri_schemes(e: &Env) -> Option<Vec<String>> {
+    let key = DataKey::UriSchemes;
+    e.storage().instance().get(&key)
+}
+
+pub fn set_uri_schemes(e: &Env, schemes: &Vec<String>) {
+    let key = DataKey::UriSchemes;
+    e.storage().instance().set(&key, schemes);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+// Linked account verifiers
+pub fn is_verifier(e: &Env, verifier: &Address) -> bool {
+    let key = DataKey::Verifier(verifier.clone());
+    e.storage().persistent().has(&key)
+}
+
+pub fn set_verifier(e: &Env, verifier: &Address, approved: bool) {
+    let key = DataKey::Verifier(verifier.clone());
+    if approved {
+        e.storage().persistent().set(&key, &true);
+        e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+// Email verifier keys
+pub fn is_email_verifier_key(e: &Env, public_key: &BytesN<32>) -> bool {
+    let key = DataKey::EmailVerifierKey(public_key.clone());
+    e.storage().persistent().has(&key)
+}
+
+pub fn set_email_verifier_key(e: &Env, public_key: &BytesN<32>, approved: bool) {
+    let key = DataKey::EmailVerifierKey(public_key.clone());
+    if approved {
+        e.storage().persistent().set(&key, &true);
+        e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn get_email_nonce(e: &Env, owner: &Address) -> u64 {
+    let key = DataKey::EmailNonce(owner.clone());
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn set_email_nonce(e: &Env, owner: &Address, nonce: u64) {
+    let key = DataKey::EmailNonce(owner.clone());
+    e.storage().persistent().set(&key, &nonce);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+// Scoring table
+pub fn get_scoring_config(e: &Env) -> Option<ScoringConfig> {
+    let key = DataKey::ScoringConfig;
+    let raw: Option<Map<Symbol, Val>> = e.storage().instance().get(&key);
+    raw.map(|raw| decode_scoring_config(e, raw))
+}
+
+pub fn set_scoring_config(e: &Env, config: &ScoringConfig) {
+    let key = DataKey::ScoringConfig;
+    e.storage().instance().set(&key, config);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+// Issuer-weighted reputation
+pub fn get_weighted_reputation(e: &Env, account: &Address) -> Option<WeightedReputation> {
+    let key = DataKey::WeightedReputation(account.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn set_weighted_reputation(e: &Env, account: &Address, reputation: &WeightedReputation) {
+    let key = DataKey::WeightedReputation(account.clone());
+    e.storage().persistent().set(&key, reputation);
+    e.storage().persistent().extend_ttl(&key, PROFILE_LIFETIME, PROFILE_LIFETIME);
+}
+
+// Schema version & migration
+pub fn get_schema_version(e: &Env) -> u32 {
+    let key = DataKey::SchemaVersion;
+    // Contracts deployed before schema versioning never stored a version
+    e.storage().instance().get(&key).unwrap_or(1)
+}
+
+pub fn set_schema_version(e: &Env, version: u32) {
+    let key = DataKey::SchemaVersion;
+    e.storage().instance().set(&key, &version);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+pub fn get_migration_cursor(e: &Env) -> u64 {
+    let key = DataKey::MigrationCursor;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn set_migration_cursor(e: &Env, cursor: u64) {
+    let key = DataKey::MigrationCursor;
+    e.storage().instance().set(&key, &cursor);
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME, INSTANCE_LIFETIME);
+}
+
+pub fn remove_migration_cursor(e: &Env) {
+    let key = DataKey::MigrationCursor;
+    e.storage().instance().remove(&key);
+}
+
 // Global counters
 pub fn get_next_claim_id(e: &Env) -> u64 {
     let key = DataKey::NextClaimId;
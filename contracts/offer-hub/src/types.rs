@@ -6,6 +6,23 @@ use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
 pub struct LinkedAccount {
     pub platform: Symbol,
     pub handle: String,
+    /// Verifier that attested the owner controls this account. Only set by
+    /// `verify_linked_account`; values sent by the owner are ignored.
+    pub verified_by: Option<Address>,
+    pub verified_at: Option<u64>,
+    /// Hash of the evidence the verifier checked (e.g. a gist)
+    pub proof_hash: Option<BytesN<32>>,
+}
+
+/// Lifecycle state of a profile
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProfileStatus {
+    Active,
+    /// Hidden from getters and scoring until the owner reactivates it
+    Deactivated,
+    /// Personal data erased; the record is kept as a tombstone
+    Deleted,
 }
 
 /// User profile with optional DID
@@ -20,6 +37,63 @@ pub struct Profile {
     pub email_hash: Option<BytesN<32>>,
     pub linked_accounts: Vec<LinkedAccount>,
     pub joined_at: u64,
+    /// Addresses allowed to move the profile to a new address if the owner loses their key
+    pub guardians: Vec<Address>,
+    /// Number of guardian approvals needed to recover the profile, 0 when recovery is off
+    pub recovery_threshold: u32,
+    /// Unique human-readable handle, see `claim_handle`
+    pub handle: Option<Symbol>,
+    /// SHA-256 of the metadata document, so clients can check what they fetched
+    pub metadata_hash: Option<BytesN<32>>,
+    pub status: ProfileStatus,
+    /// Whether `email_hash` was attested by a registered email verifier, see `verify_email`
+    pub email_verified: bool,
+    /// Incremented on every change, starting at 1 on registration
+    pub version: u32,
+    pub updated_at: u64,
+    /// Address the profile was registered with. Its history is kept under this
+    /// address, so it follows the profile through rotations and recoveries.
+    pub registered_as: Address,
+}
+
+/// Record of one profile change, kept per version from version 2 on
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileHistoryEntry {
+    pub version: u32,
+    pub updated_at: u64,
+    /// SHA-256 of the XDR of the profile as it was before this change
+    pub previous_hash: BytesN<32>,
+}
+
+/// A page of profile history entries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileHistoryPage {
+    pub entries: Vec<ProfileHistoryEntry>,
+    /// Version to pass as cursor to fetch the next page, `None` when there are no more entries
+    pub next_cursor: Option<u32>,
+}
+
+/// Owner of a handle. A released handle keeps its record during the
+/// cooldown, when only its previous owner can claim it back.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HandleRecord {
+    pub owner: Address,
+    pub released_at: Option<u64>,
+}
+
+/// Request by guardians to move a profile to a new address. Each guardian
+/// backs one address at a time, so several requests can be pending at once.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryRequest {
+    pub new_owner: Address,
+    /// Guardians that approved the request
+    pub approvals: Vec<Address>,
+    /// Time from which the recovery can be completed, set once enough guardians approved
+    pub executable_at: Option<u64>,
 }
 
 /// Status of a claim
@@ -29,6 +103,40 @@ pub enum ClaimStatus {
     Pending,
     Approved,
     Rejected,
+    Revoked,
+    /// Reported by getters for pending or approved claims past `valid_until`;
+    /// never stored
+    Expired,
+}
+
+/// Reason given by an issuer when revoking a claim
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RevocationReason {
+    IssuedInError,
+    Fraud,
+    Superseded,
+    Other(String),
+}
+
+/// Document attached to a claim, e.g. a deliverable, a contract or a review
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimEvidence {
+    /// What the document is (e.g. `deliverable`, `contract`, `review`)
+    pub kind: Symbol,
+    /// Where the document lives, validated like profile metadata URIs
+    pub uri: String,
+    /// SHA-256 of the document
+    pub hash: BytesN<32>,
+}
+
+/// Period in which a claim counts towards reputation. Either bound can be left open.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidityWindow {
+    pub valid_from: Option<u64>,
+    pub valid_until: Option<u64>,
 }
 
 /// Claim structure representing a skill or achievement
@@ -41,6 +149,124 @@ pub struct Claim {
     pub claim_type: String,
     pub proof_hash: BytesN<32>,
     pub status: ClaimStatus,
+    pub revoked_at: Option<u64>,
+    /// Off-chain claim details, validated like profile metadata URIs
+    pub metadata_uri: Option<String>,
+    pub evidence: Vec<ClaimEvidence>,
+    /// Time the claim starts counting towards reputation, if later than issuance
+    pub valid_from: Option<u64>,
+    /// Time the claim expires, e.g. for certifications that must be renewed
+    pub valid_until: Option<u64>,
+    /// Ledger time the claim was added; `None` for claims added before it was recorded
+    pub issued_at: Option<u64>,
+}
+
+/// Points awarded for approved claims of one claim type, or of every claim
+/// type starting with a prefix
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoringRule {
+    /// Claim type (e.g. `job_completed`), or prefix (e.g. `skill_`) when `is_prefix` is set
+    pub claim_type: String,
+    pub is_prefix: bool,
+    /// Points per approved claim
+    pub points: u32,
+    /// Maximum points all claims matching this rule can add up to
+    pub max_points: Option<u32>,
+}
+
+/// Admin-managed table used by `get_reputation_score`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoringConfig {
+    pub rules: Vec<ScoringRule>,
+    /// Points per approved claim whose type matches no rule
+    pub default_points: u32,
+    /// Maximum points all claims matching no rule can add up to
+    pub default_max_points: Option<u32>,
+    /// Points added per full week since the profile was created
+    pub weekly_age_bonus: u32,
+    /// Ignore claims an account issued to itself
+    pub ignore_self_claims: bool,
+    /// Ignore claims from issuers without an active profile
+    pub require_issuer_profile: bool,
+    /// Share of the previous claim's points, in basis points, earned by each
+    /// further claim from the same issuer. `None` gives every claim full points.
+    pub repeat_issuer_factor_bps: Option<u32>,
+    /// Seconds over which the points of an expired claim fall linearly to
+    /// zero. `0` stops counting claims as soon as they expire.
+    pub expiry_decay_period: u64,
+    /// Seconds after which a claim is worth half its points, halving again
+    /// every further period. `0` keeps claims at full points forever.
+    pub claim_half_life: u64,
+    /// Maximum points the weekly age bonus can add up to
+    pub max_age_points: Option<u32>,
+}
+
+/// Points earned from the approved claims matching one scoring rule
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimTypePoints {
+    /// Claim type or prefix of the rule, empty for claims matching no rule
+    pub claim_type: String,
+    pub is_prefix: bool,
+    /// Number of approved claims matching the rule
+    pub claims: u32,
+    /// Points after the rule's cap is applied
+    pub points: u32,
+}
+
+/// Explanation of a reputation score, returned by `get_reputation_breakdown`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationBreakdown {
+    pub claim_points: Vec<ClaimTypePoints>,
+    pub age_points: u32,
+    pub approved_claims: u32,
+    pub pending_claims: u32,
+    pub rejected_claims: u32,
+    pub revoked_claims: u32,
+    /// Approved claims past `valid_until`, counted with decayed points if any
+    pub expired_claims: u32,
+    /// Approved claims left out by the self-claim and issuer profile rules,
+    /// or not valid yet
+    pub ignored_claims: u32,
+    /// Number of different issuers among the approved claims that count
+    pub distinct_issuers: u32,
+    pub total: u32,
+}
+
+/// Issuer-weighted reputation score stored for one epoch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WeightedReputation {
+    pub score: u32,
+    /// Epoch the score was computed in, see `WEIGHTED_REPUTATION_EPOCH`
+    pub epoch: u64,
+    /// Score stored in the epoch before `epoch`, which keeps weighting the
+    /// claims the account issued until `epoch` ends
+    pub previous_score: u32,
+}
+
+/// Group of state-changing entry points that can be paused together
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PauseScope {
+    /// Every pausable entry point
+    All,
+    /// Profile registration and updates
+    Profiles,
+    /// Claim issuance and status changes
+    Claims,
+}
+
+/// A page of claims returned by the paginated claim queries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimPage {
+    pub claims: Vec<Claim>,
+    /// Cursor to pass to fetch the next page, `None` when there are no more claims
+    pub next_cursor: Option<u32>,
 }
 
 /// Storage keys for the contract
@@ -49,8 +275,46 @@ pub struct Claim {
 pub enum DataKey {
     Profile(Address),
     Claim(u64),
-    UserClaims(Address),
-    IssuerClaims(Address),
+    /// Bucket of claim IDs received by a user: (user, bucket index)
+    UserClaims(Address, u32),
+    UserClaimCount(Address),
+    /// Bucket of claim IDs issued by a user: (issuer, bucket index)
+    IssuerClaims(Address, u32),
+    IssuerClaimCount(Address),
     NextClaimId,
+    RevocationReason(u64),
+    Admin,
+    PendingAdmin,
+    SchemaVersion,
+    MigrationCursor,
+    Paused(PauseScope),
+    ScoringConfig,
+    /// Reverse index from a linked DID to its profile owner
+    Did(String),
+    DidMethods,
+    /// Address a profile was rotated to, kept under the old address
+    RotatedTo(Address),
+    /// Pending guardian recovery of a profile, keyed by its owner
+    Recovery(Address),
+    /// Reverse index from a handle to its owner
+    Handle(Symbol),
+    Platforms,
+    UriSchemes,
+    /// Addresses approved by the admin to verify linked accounts
+    Verifier(Address),
+    /// ed25519 public keys allowed to sign email attestations
+    EmailVerifierKey(BytesN<32>),
+    /// Next email attestation nonce of an owner
+    EmailNonce(Address),
+    /// History entry of a profile: (address it was registered with, version)
+    ProfileHistory(Address, u32),
+    /// Bucket of claim IDs issued with a proof hash: (proof hash, bucket index)
+    ProofHashClaims(BytesN<32>, u32),
+    ProofHashClaimCount(BytesN<32>),
+    /// Latest live claim issued with a proof hash to an identity:
+    /// (proof hash, address the receiver was registered with)
+    ProofHash(BytesN<32>, Address),
+    /// Last stored issuer-weighted reputation of an account
+    WeightedReputation(Address),
 }
 